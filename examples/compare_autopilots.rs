@@ -0,0 +1,57 @@
+// Plays the same boards with every autopilot and prints how each one did.
+//
+// cargo run --release --example compare_autopilots
+use survival::autopilot::Autopilot;
+use survival::hunt::CreeperStrategy;
+use survival::lookahead::LookaheadConfig;
+use survival::maze::Maze;
+use survival::mcts::MctsConfig;
+use survival::model::{Connectivity, GameConfig};
+use survival::simulation::run_batch;
+
+const GAMES: u64 = 100;
+const MAX_TICKS: i16 = 500;
+
+fn main() {
+    // Searches stop at their depth or iterations instead of the clock, so every run
+    // prints the same.
+    let lookahead = LookaheadConfig {
+        time_budget_ms: u32::MAX,
+        ..LookaheadConfig::default()
+    };
+    let monte_carlo = MctsConfig {
+        time_budget_ms: u32::MAX,
+        ..MctsConfig::default()
+    };
+    let autopilots = vec![
+        ("greedy", Autopilot::Greedy),
+        ("lookahead", Autopilot::Lookahead(lookahead)),
+        ("monte carlo", Autopilot::MonteCarlo(monte_carlo)),
+    ];
+    for creeper_strategy in [CreeperStrategy::Chase, CreeperStrategy::Pack] {
+        for (name, autopilot) in &autopilots {
+            let config = GameConfig {
+                creepers: 10,
+                rows: 12,
+                columns: 24,
+                creeper_strategy: creeper_strategy.clone(),
+                autopilot: autopilot.clone(),
+                connectivity: Connectivity::Eight,
+                wrap: false,
+                maze: Maze::Open,
+            };
+            let summary = run_batch(&config, 0..GAMES, MAX_TICKS);
+            println!(
+                "{:?} creepers, {} autopilot: won {:.1}% ({} won, {} lost, {} errors, {} timeouts), {:.1} ticks per win",
+                creeper_strategy,
+                name,
+                summary.win_rate() * 100f64,
+                summary.won,
+                summary.lost,
+                summary.errors,
+                summary.timeouts,
+                summary.average_ticks_to_win()
+            );
+        }
+    }
+}
@@ -0,0 +1,85 @@
+"""Smoke test of the Python bindings.
+
+Build and install the wheel first:
+
+    maturin build --release && pip install target/wheels/survival-*.whl
+    python -m unittest discover python/tests
+"""
+import unittest
+
+import survival
+
+
+class GameTest(unittest.TestCase):
+    def test_new_game(self):
+        game = survival.Game(rows=8, columns=6, creepers=3, seed=7)
+        self.assertEqual(game.status, "idle")
+        self.assertEqual((game.rows, game.columns), (8, 6))
+        self.assertEqual(len(game.creepers), 3)
+        self.assertNotIn(game.ferris, game.creepers)
+        self.assertNotIn(game.target, game.creepers)
+        self.assertNotEqual(game.ferris, game.target)
+        self.assertEqual(game.path[-1], game.target)
+
+    def test_same_seed_same_board(self):
+        first = survival.Game(rows=8, columns=6, creepers=3, seed=7)
+        second = survival.Game(rows=8, columns=6, creepers=3, seed=7)
+        self.assertEqual(first.ferris, second.ferris)
+        self.assertEqual(first.creepers, second.creepers)
+        self.assertEqual(first.observation(), second.observation())
+
+    def test_move_and_tick(self):
+        game = survival.Game(rows=8, columns=6, creepers=2, seed=1)
+        self.assertEqual(game.move_ferris("down"), game.status)
+        for _ in range(200):
+            if game.tick() != "playing":
+                break
+        self.assertIn(game.status, ("won", "lost"))
+        with self.assertRaises(ValueError):
+            game.move_ferris("sideways")
+
+    def test_observation(self):
+        game = survival.Game(rows=8, columns=6, creepers=3, seed=3)
+        channels, rows, columns = game.observation_shape()
+        self.assertEqual((rows, columns), (8, 6))
+        observation = game.observation()
+        self.assertEqual(len(observation), channels * rows * columns)
+        self.assertEqual(sum(observation[: rows * columns]), 1.0)
+
+
+class EnvTest(unittest.TestCase):
+    def test_episode(self):
+        env = survival.Env(rows=8, columns=6, creepers=2, max_steps=50)
+        self.assertEqual(survival.Env.actions()[0], "stay")
+        observation = env.reset(5)
+        self.assertEqual(len(observation), 4 * 8 * 6)
+        done = False
+        steps = 0
+        while not done:
+            observation, reward, done, info = env.step(1)
+            self.assertIsInstance(reward, float)
+            steps += 1
+        self.assertEqual(info["tick"], steps)
+        self.assertLessEqual(steps, 50)
+
+    def test_settings(self):
+        env = survival.Env(
+            rows=8,
+            columns=6,
+            creepers=0,
+            max_steps=3,
+            autopilot="lookahead",
+            connectivity="hex",
+            maze="caves",
+            step_reward=-1.0,
+        )
+        env.reset(2)
+        observation, reward, done, info = env.step(0)
+        self.assertEqual(reward, -1.0)
+        self.assertEqual(info["status"], "playing")
+        with self.assertRaises(ValueError):
+            survival.Env(maze="hedge")
+
+
+if __name__ == "__main__":
+    unittest.main()
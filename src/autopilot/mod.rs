@@ -0,0 +1,144 @@
+use crate::lookahead::{self, LookaheadConfig};
+use crate::mcts::{self, MctsConfig};
+use crate::model::{Game, Location};
+use anyhow::{anyhow, Result};
+use std::{fmt, str::FromStr};
+
+/**
+ * How Ferris moves on its own when the player does not press the arrows.
+ * Use Greedy to follow the Dijkstra path home, it steers clear of creepers but only
+ * looks at where they are right now.
+ * Use Lookahead to search a few turns of Ferris and creeper moves before each step.
+ * Use MonteCarlo to pick the move that does best in random playouts of the game.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Autopilot {
+    #[default]
+    Greedy,
+    Lookahead(LookaheadConfig),
+    MonteCarlo(MctsConfig),
+}
+
+impl FromStr for Autopilot {
+    type Err = anyhow::Error;
+
+    /**
+     * Parses the name of an autopilot, searching autopilots use their default settings.
+     */
+    fn from_str(autopilot: &str) -> Result<Self, Self::Err> {
+        match autopilot.to_lowercase().as_str() {
+            "greedy" => Ok(Autopilot::Greedy),
+            "lookahead" => Ok(Autopilot::Lookahead(LookaheadConfig::default())),
+            "montecarlo" | "monte_carlo" | "mcts" => {
+                Ok(Autopilot::MonteCarlo(MctsConfig::default()))
+            }
+            _ => Err(anyhow!(
+                "unknown autopilot {:?}, use greedy, lookahead or montecarlo",
+                autopilot
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Autopilot {
+    /**
+     * Name of the autopilot, the settings of searching autopilots are left out.
+     */
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Autopilot::Greedy => write!(f, "greedy"),
+            Autopilot::Lookahead(_) => write!(f, "lookahead"),
+            Autopilot::MonteCarlo(_) => write!(f, "montecarlo"),
+        }
+    }
+}
+
+/**
+ * How much the autopilot looked into moving Ferris to `location`.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveStats {
+    pub location: Location,
+    pub visits: u32,
+    // Average reward of the visits, between 0 (caught) and 1 (home right away).
+    pub value: f64,
+}
+
+/**
+ * Where the autopilot wants Ferris to go.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Plan {
+    // Path home, the first location is where Ferris moves on this tick.
+    pub path: Vec<Location>,
+    // Per move statistics of autopilots that search, empty otherwise.
+    pub move_stats: Vec<MoveStats>,
+}
+
+impl Autopilot {
+    /**
+     * Computes Ferris's plan for the last state of `game`, `tick` is the tick Ferris is
+     * about to move on.
+     */
+    pub fn plan(&self, game: &Game, tick: i16) -> Result<Plan> {
+        let state = game
+            .moves
+            .last()
+            .ok_or_else(|| anyhow!("game has no moves"))?;
+        let ferris_location = &state.ferris.location;
+        let (next, move_stats) = match self {
+            Autopilot::Greedy => {
+                let mut path = game.path_home(ferris_location);
+                // A way home past the creepers can start on one, wait for it to move on.
+                let blocked = path.first().is_some_and(|first| {
+                    state
+                        .creepers
+                        .iter()
+                        .any(|creeper| creeper.location == *first)
+                });
+                if blocked {
+                    path.insert(0, ferris_location.clone());
+                }
+                return Ok(Plan {
+                    path,
+                    move_stats: vec![],
+                });
+            }
+            Autopilot::Lookahead(config) => {
+                let next = lookahead::best_move(game, state, tick, config);
+                (next, vec![])
+            }
+            Autopilot::MonteCarlo(config) => {
+                let move_stats = mcts::search(game, state, tick, config)?;
+                let next = mcts::most_visited(&move_stats);
+                (next, move_stats)
+            }
+        };
+        let next = next.unwrap_or_else(|| ferris_location.clone());
+        let mut path = vec![next.clone()];
+        if next != game.target {
+            path.extend(game.path_home(&next));
+        }
+        Ok(Plan { path, move_stats })
+    }
+}
+
+/**
+ * Milliseconds elapsed since some fixed point in time, only useful to measure durations.
+ */
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+/**
+ * Milliseconds elapsed since some fixed point in time, only useful to measure durations.
+ */
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now_ms() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs_f64() * 1000f64)
+        .unwrap_or(0f64)
+}
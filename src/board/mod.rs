@@ -0,0 +1,497 @@
+use crate::model::{Connectivity, Direction, Location};
+
+/**
+ * Index of a line of the board on screen, the top line is row 0 and `Direction::Up`
+ * goes to the previous row. A board has `Board.rows` of them.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Row(pub i32);
+
+/**
+ * Index of a cell along a line of the board, the left cell is column 0 and
+ * `Direction::Left` goes to the previous column. A line has `Board.columns` cells.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Col(pub i32);
+
+// Distance between the centers of two lines of hexes, in cell widths (sqrt(3) / 2).
+const HEX_LINE: f64 = 0.866_025_4;
+// Height of a pointy-top hex one cell wide (2 / sqrt(3)).
+const HEX_HEIGHT: f64 = 1.154_700_5;
+
+/**
+ * Axial coordinates (q, r) of a location on a hex board. Hexes are pointy-top, each
+ * row is a line of hexes and odd rows are shifted half a cell right.
+ */
+pub fn axial(location: &Location) -> (i32, i32) {
+    let r = location.row().0;
+    (location.col().0 - (r - (r & 1)) / 2, r)
+}
+
+/**
+ * Location of the hex at axial coordinates (q, r), the reverse of `axial`.
+ */
+pub fn from_axial(q: i32, r: i32) -> Location {
+    Location::at(Row(r), Col(q + (r - (r & 1)) / 2))
+}
+
+/**
+ * Center of a hex on screen in cell widths, neighbors are 1 apart.
+ */
+pub fn hex_center(location: &Location) -> (f64, f64) {
+    let (Row(row), Col(col)) = (location.row(), location.col());
+    let shift = if row & 1 == 1 { 0.5 } else { 0f64 };
+    (
+        col as f64 + shift + 0.5,
+        row as f64 * HEX_LINE + HEX_HEIGHT / 2f64,
+    )
+}
+
+/**
+ * Change of the axial coordinates of a step in `direction`, hexes have no neighbor
+ * straight up or down.
+ */
+fn hex_offset(direction: Direction) -> Option<(i32, i32)> {
+    match direction {
+        Direction::Left => Some((-1, 0)),
+        Direction::Right => Some((1, 0)),
+        Direction::UpLeft => Some((0, -1)),
+        Direction::UpRight => Some((1, -1)),
+        Direction::DownLeft => Some((-1, 1)),
+        Direction::DownRight => Some((0, 1)),
+        Direction::Up | Direction::Down => None,
+    }
+}
+
+/**
+ * Size and rules of the board, every question about which cells exist and which
+ * ones are next to each other goes through it.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Board {
+    pub rows: i32,
+    pub columns: i32,
+    pub connectivity: Connectivity,
+    // Edges wrap around, stepping off one side comes back on the other like on a torus.
+    // Hex boards need an even number of rows for the lines to line up.
+    pub wrap: bool,
+}
+
+impl Board {
+    pub fn new(rows: i32, columns: i32, connectivity: Connectivity) -> Board {
+        Board {
+            rows: rows.max(0),
+            columns: columns.max(0),
+            connectivity,
+            wrap: false,
+        }
+    }
+
+    pub fn contains(&self, location: &Location) -> bool {
+        (0..self.rows).contains(&location.row().0) && (0..self.columns).contains(&location.col().0)
+    }
+
+    /**
+     * Number of cells.
+     */
+    pub fn len(&self) -> usize {
+        (self.rows * self.columns) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+     * Position of `location` in a vector with one entry per cell, None when it is off
+     * the board. Cells are numbered line by line from the top left corner.
+     */
+    pub fn index(&self, location: &Location) -> Option<usize> {
+        if !self.contains(location) {
+            return None;
+        }
+        Some((location.row().0 * self.columns + location.col().0) as usize)
+    }
+
+    /**
+     * Location of the cell at `index`, the reverse of `index`.
+     */
+    pub fn location(&self, index: usize) -> Option<Location> {
+        if index >= self.len() {
+            return None;
+        }
+        let index = index as i32;
+        Some(Location::at(
+            Row(index / self.columns),
+            Col(index % self.columns),
+        ))
+    }
+
+    /**
+     * Every cell in index order, which is the order a CSS grid lays them out, left to
+     * right and then top to bottom.
+     */
+    pub fn locations(&self) -> impl Iterator<Item = Location> + '_ {
+        (0..self.len()).filter_map(move |index| self.location(index))
+    }
+
+    /**
+     * Cell one step from `location` towards `direction`, None when the step leaves the
+     * board or the connectivity does not allow it.
+     */
+    pub fn neighbor(&self, location: &Location, direction: Direction) -> Option<Location> {
+        if !self.connectivity.allows(direction) {
+            return None;
+        }
+        let neighbor = match self.connectivity {
+            Connectivity::Hex => {
+                let (q, r) = axial(location);
+                let (d_q, d_r) = hex_offset(direction)?;
+                from_axial(q + d_q, r + d_r)
+            }
+            Connectivity::Four | Connectivity::Eight => {
+                let (d_row, d_col) = direction.offset();
+                location.offset(d_row, d_col)
+            }
+        };
+        let neighbor = if self.wrap && !self.is_empty() {
+            Location::at(
+                Row(neighbor.row().0.rem_euclid(self.rows)),
+                Col(neighbor.col().0.rem_euclid(self.columns)),
+            )
+        } else {
+            neighbor
+        };
+        Some(neighbor).filter(|neighbor| self.contains(neighbor))
+    }
+
+    /**
+     * Cells next to `location` that are on the board, in the order of
+     * `Connectivity::directions`. On small wrapped boards a cell shows up once even
+     * when several directions lead to it.
+     */
+    pub fn neighbors<'a>(&'a self, location: &'a Location) -> impl Iterator<Item = Location> + 'a {
+        let mut seen: Vec<Location> = vec![];
+        self.connectivity
+            .directions()
+            .iter()
+            .filter_map(move |direction| self.neighbor(location, *direction))
+            .filter(move |neighbor| {
+                if neighbor == location || seen.contains(neighbor) {
+                    return false;
+                }
+                seen.push(neighbor.clone());
+                true
+            })
+    }
+
+    /**
+     * Location one step from `location` towards `direction`. Square boards with walls
+     * clamp the step like `Location::move_direction`, otherwise Ferris stays put when
+     * there is no neighbor that way.
+     */
+    pub fn step(&self, location: &Location, direction: Direction) -> Location {
+        match self.connectivity {
+            Connectivity::Four | Connectivity::Eight if !self.wrap => {
+                location.move_direction(direction, self.rows, self.columns)
+            }
+            _ => self
+                .neighbor(location, direction)
+                .unwrap_or_else(|| location.clone()),
+        }
+    }
+
+    /**
+     * Where `to` is seen from anywhere on the board, itself and its copies one board
+     * away in every direction when edges wrap.
+     */
+    fn images(&self, to: &Location) -> Vec<Location> {
+        if !self.wrap {
+            return vec![to.clone()];
+        }
+        let mut images = vec![];
+        for d_row in [-self.rows, 0, self.rows] {
+            for d_col in [-self.columns, 0, self.columns] {
+                images.push(to.offset(d_row, d_col));
+            }
+        }
+        images
+    }
+
+    /**
+     * Number of moves needed to walk from one location to another on an open board,
+     * going through the edges when they wrap.
+     */
+    pub fn steps(&self, from: &Location, to: &Location) -> i32 {
+        self.images(to)
+            .iter()
+            .map(|to| self.connectivity.steps(from, to))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /**
+     * Length of the shortest straight walk between two locations, see
+     * `Connectivity::distance`.
+     */
+    pub fn distance(&self, from: &Location, to: &Location) -> f32 {
+        self.images(to)
+            .iter()
+            .map(|to| self.connectivity.distance(from, to))
+            .fold(f32::MAX, f32::min)
+    }
+
+    /**
+     * Allowed direction that points the closest to a move of `dx`, `dy` on screen, None
+     * when there is no move.
+     */
+    pub fn direction_towards(&self, dx: f64, dy: f64) -> Option<Direction> {
+        if dx == 0f64 && dy == 0f64 {
+            return None;
+        }
+        let screen_vector = |direction: Direction| match (self.connectivity, hex_offset(direction))
+        {
+            (Connectivity::Hex, Some((d_q, d_r))) => {
+                (d_q as f64 + d_r as f64 / 2f64, d_r as f64 * HEX_LINE)
+            }
+            _ => {
+                let (d_row, d_col) = direction.offset();
+                (d_col as f64, d_row as f64)
+            }
+        };
+        let alignment = |direction: &Direction| {
+            let (x, y) = screen_vector(*direction);
+            (x * dx + y * dy) / (x.hypot(y) * dx.hypot(dy))
+        };
+        self.connectivity
+            .directions()
+            .iter()
+            .copied()
+            .max_by(|a, b| alignment(a).total_cmp(&alignment(b)))
+    }
+
+    /**
+     * Width and height of the board on screen, in cell widths.
+     */
+    pub fn screen_size(&self) -> (f64, f64) {
+        match self.connectivity {
+            Connectivity::Hex if self.rows > 0 => (
+                self.columns as f64 + if self.rows > 1 { 0.5 } else { 0f64 },
+                (self.rows - 1) as f64 * HEX_LINE + HEX_HEIGHT,
+            ),
+            _ => (self.columns as f64, self.rows as f64),
+        }
+    }
+
+    /**
+     * Top left corner of the square, one cell wide, `location` is drawn in on screen.
+     */
+    pub fn screen_position(&self, location: &Location) -> (f64, f64) {
+        match self.connectivity {
+            Connectivity::Hex => {
+                let (x, y) = hex_center(location);
+                (x - 0.5, y - 0.5)
+            }
+            Connectivity::Four | Connectivity::Eight => {
+                (location.col().0 as f64, location.row().0 as f64)
+            }
+        }
+    }
+
+    /**
+     * Corners of the outline of `location` on screen, clockwise.
+     */
+    pub fn outline(&self, location: &Location) -> Vec<(f64, f64)> {
+        let (x, y) = self.screen_position(location);
+        match self.connectivity {
+            Connectivity::Hex => {
+                let top = (HEX_HEIGHT - 1f64) / 2f64;
+                vec![
+                    (x + 0.5, y - top),
+                    (x + 1f64, y - top + HEX_HEIGHT / 4f64),
+                    (x + 1f64, y + 1f64 + top - HEX_HEIGHT / 4f64),
+                    (x + 0.5, y + 1f64 + top),
+                    (x, y + 1f64 + top - HEX_HEIGHT / 4f64),
+                    (x, y - top + HEX_HEIGHT / 4f64),
+                ]
+            }
+            Connectivity::Four | Connectivity::Eight => {
+                vec![(x, y), (x + 1f64, y), (x + 1f64, y + 1f64), (x, y + 1f64)]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Connectivity, Direction, Location};
+
+    use super::{axial, from_axial, Board, Col, Row};
+
+    #[test]
+    fn index_round_trip() {
+        let board = Board::new(3, 4, Connectivity::Eight);
+        assert_eq!(board.len(), 12);
+        for (index, location) in board.locations().enumerate() {
+            assert_eq!(board.index(&location), Some(index));
+        }
+        assert_eq!(board.location(12), None);
+        assert_eq!(board.index(&Location::at(Row(0), Col(4))), None);
+        assert_eq!(board.index(&Location::at(Row(-1), Col(0))), None);
+        assert_eq!(board.location(5), Some(Location::at(Row(1), Col(1))));
+    }
+
+    #[test]
+    fn neighbors_stay_on_the_board() {
+        let board = Board::new(3, 4, Connectivity::Eight);
+        let corner: Vec<Location> = board.neighbors(&Location::at(Row(2), Col(3))).collect();
+        assert_eq!(
+            corner,
+            vec![
+                Location::at(Row(1), Col(2)),
+                Location::at(Row(1), Col(3)),
+                Location::at(Row(2), Col(2))
+            ]
+        );
+        let board = Board::new(3, 4, Connectivity::Four);
+        assert_eq!(board.neighbors(&Location::at(Row(1), Col(1))).count(), 4);
+    }
+
+    #[test]
+    fn screen_layout() {
+        let board = Board::new(2, 3, Connectivity::Eight);
+        assert_eq!(board.screen_size(), (3f64, 2f64));
+        let order: Vec<(f64, f64)> = board
+            .locations()
+            .map(|location| board.screen_position(&location))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (0f64, 0f64),
+                (1f64, 0f64),
+                (2f64, 0f64),
+                (0f64, 1f64),
+                (1f64, 1f64),
+                (2f64, 1f64)
+            ]
+        );
+        // Up goes to the previous row, the line above on screen.
+        let location = board.step(&Location::at(Row(1), Col(1)), Direction::Up);
+        assert_eq!(board.screen_position(&location), (1f64, 0f64));
+        assert_eq!(
+            board.direction_towards(10f64, -9f64),
+            Some(Direction::UpRight)
+        );
+        assert_eq!(board.direction_towards(0f64, 0f64), None);
+    }
+
+    #[test]
+    fn axial_round_trip() {
+        for location in Board::new(6, 5, Connectivity::Hex).locations() {
+            let (q, r) = axial(&location);
+            assert_eq!(from_axial(q, r), location);
+        }
+        assert_eq!(axial(&Location::at(Row(3), Col(2))), (1, 3));
+    }
+
+    #[test]
+    fn hex_neighbors() {
+        let board = Board::new(5, 5, Connectivity::Hex);
+        // Odd columns are shifted right, their diagonal neighbors are one row further.
+        let odd: Vec<Location> = board.neighbors(&Location::at(Row(1), Col(2))).collect();
+        assert_eq!(
+            odd,
+            vec![
+                Location::at(Row(0), Col(2)),
+                Location::at(Row(0), Col(3)),
+                Location::at(Row(1), Col(1)),
+                Location::at(Row(1), Col(3)),
+                Location::at(Row(2), Col(2)),
+                Location::at(Row(2), Col(3)),
+            ]
+        );
+        let even: Vec<Location> = board.neighbors(&Location::at(Row(2), Col(2))).collect();
+        assert_eq!(
+            even,
+            vec![
+                Location::at(Row(1), Col(1)),
+                Location::at(Row(1), Col(2)),
+                Location::at(Row(2), Col(1)),
+                Location::at(Row(2), Col(3)),
+                Location::at(Row(3), Col(1)),
+                Location::at(Row(3), Col(2)),
+            ]
+        );
+        for neighbor in &even {
+            let distance = Connectivity::Hex.distance(&Location::at(Row(2), Col(2)), neighbor);
+            assert!((distance - 1f32).abs() < 1e-4);
+        }
+        assert_eq!(board.neighbors(&Location::at(Row(0), Col(0))).count(), 2);
+        assert_eq!(
+            board.step(&Location::at(Row(2), Col(2)), Direction::Up),
+            Location::at(Row(2), Col(2))
+        );
+        assert_eq!(
+            board.step(&Location::at(Row(0), Col(0)), Direction::UpLeft),
+            Location::at(Row(0), Col(0))
+        );
+        assert_eq!(
+            board
+                .direction_towards(0f64, -1f64)
+                .map(|direction| board.connectivity.allows(direction)),
+            Some(true)
+        );
+        assert_eq!(
+            board.direction_towards(-1f64, -2f64),
+            Some(Direction::UpLeft)
+        );
+    }
+
+    #[test]
+    fn wrapped_board() {
+        let board = Board {
+            wrap: true,
+            ..Board::new(4, 5, Connectivity::Eight)
+        };
+        let corner: Vec<Location> = board.neighbors(&Location::at(Row(0), Col(0))).collect();
+        assert_eq!(corner.len(), 8);
+        assert!(corner.contains(&Location::at(Row(3), Col(4))));
+        assert_eq!(
+            board.step(&Location::at(Row(0), Col(0)), Direction::UpLeft),
+            Location::at(Row(3), Col(4))
+        );
+        assert_eq!(
+            board.steps(&Location::at(Row(0), Col(0)), &Location::at(Row(3), Col(4))),
+            1
+        );
+        assert_eq!(
+            board.distance(&Location::at(Row(1), Col(0)), &Location::at(Row(1), Col(4))),
+            1f32
+        );
+        // Going left and right both reach the other row of a two row board.
+        let narrow = Board {
+            wrap: true,
+            ..Board::new(4, 2, Connectivity::Four)
+        };
+        let neighbors: Vec<Location> = narrow.neighbors(&Location::at(Row(0), Col(0))).collect();
+        assert_eq!(
+            neighbors,
+            vec![
+                Location::at(Row(3), Col(0)),
+                Location::at(Row(0), Col(1)),
+                Location::at(Row(1), Col(0))
+            ]
+        );
+        let hex = Board {
+            wrap: true,
+            ..Board::new(4, 4, Connectivity::Hex)
+        };
+        for location in hex.locations() {
+            assert_eq!(hex.neighbors(&location).count(), 6);
+            for neighbor in hex.neighbors(&location) {
+                assert!(hex.neighbors(&neighbor).any(|back| back == location));
+            }
+        }
+    }
+}
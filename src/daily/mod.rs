@@ -0,0 +1,235 @@
+use crate::autopilot::Autopilot;
+use crate::hunt::CreeperStrategy;
+use crate::maze::Maze;
+use crate::model::{Connectivity, Game, GameConfig, Status};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Emojis of the summary grid, one per Ferris move and one for the end of the run.
+const CLOSER: char = '🟩';
+const AWAY: char = '🟨';
+const DANGER: char = '🟥';
+const HOME: char = '🏠';
+const CAUGHT: char = '💀';
+// Emojis on every line of the summary grid.
+const SUMMARY_WIDTH: usize = 10;
+
+/**
+ * Date of a daily challenge as `YYYY-MM-DD`.
+ */
+pub fn date_key(year: u32, month: u32, day: u32) -> String {
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/**
+ * Seed of the board of a day, the same everywhere. It does not rely on the std hasher
+ * which is allowed to change between Rust releases.
+ */
+pub fn daily_seed(year: u32, month: u32, day: u32) -> u64 {
+    // splitmix64 of the date written as YYYYMMDD.
+    let mut z = (year as u64 * 10_000 + month as u64 * 100 + day as u64)
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/**
+ * Settings of the daily challenge, the same every day so only the board changes.
+ */
+pub fn daily_config() -> GameConfig {
+    GameConfig {
+        creepers: 10,
+        rows: 12,
+        columns: 24,
+        creeper_strategy: CreeperStrategy::Chase,
+        autopilot: Autopilot::Greedy,
+        connectivity: Connectivity::Eight,
+        wrap: false,
+        maze: Maze::Open,
+    }
+}
+
+/**
+ * How a daily challenge went.
+ */
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct DailyResult {
+    pub date: String,
+    pub won: bool,
+    pub moves: usize,
+    pub seconds: f64,
+    // Emoji grid of the run, see `summary`.
+    pub summary: String,
+}
+
+impl DailyResult {
+    /**
+     * Result of a finished run of the challenge of `date` that took `seconds`.
+     */
+    pub fn new(game: &Game, date: &str, seconds: f64) -> DailyResult {
+        DailyResult {
+            date: date.to_string(),
+            won: game.status == Status::Won,
+            moves: game.ferris_moves(),
+            seconds,
+            summary: summary(game, date, seconds),
+        }
+    }
+}
+
+/**
+ * Spoiler free summary of a run that can be pasted anywhere. Every Ferris move is a
+ * square, green when Ferris got closer to home, red when a creeper ended up next to
+ * Ferris and yellow otherwise, the run ends with a house or a skull.
+ */
+pub fn summary(game: &Game, date: &str, seconds: f64) -> String {
+    let board = game.board();
+    let mut squares: Vec<char> = game
+        .moves
+        .windows(2)
+        .filter(|pair| pair[0].ferris.location != pair[1].ferris.location)
+        .map(|pair| {
+            let (before, after) = (&pair[0].ferris.location, &pair[1].ferris.location);
+            let danger = pair[1]
+                .creepers
+                .iter()
+                .any(|creeper| board.steps(&creeper.location, after) <= 1);
+            if danger {
+                DANGER
+            } else if board.steps(after, &game.target) < board.steps(before, &game.target) {
+                CLOSER
+            } else {
+                AWAY
+            }
+        })
+        .collect();
+    let outcome = match game.status {
+        Status::Won => {
+            squares.push(HOME);
+            format!("home in {} moves", game.ferris_moves())
+        }
+        Status::Lost => {
+            squares.push(CAUGHT);
+            format!("caught after {} moves", game.ferris_moves())
+        }
+        _ => format!("{} moves so far", game.ferris_moves()),
+    };
+    let grid: Vec<String> = squares
+        .chunks(SUMMARY_WIDTH)
+        .map(|line| line.iter().collect())
+        .collect();
+    format!(
+        "Escape the creepers {}: {}, {:.0}s\n{}",
+        date,
+        outcome,
+        seconds,
+        grid.join("\n")
+    )
+}
+
+/**
+ * Daily results of this player by date.
+ */
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct DailyLog {
+    pub results: BTreeMap<String, DailyResult>,
+}
+
+impl DailyLog {
+    /**
+     * Log saved with `to_json`, an empty log when the json can not be read.
+     */
+    pub fn from_json(json: &str) -> DailyLog {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /**
+     * Keeps `result` unless the challenge of that day was already played, only the
+     * first try of a day counts.
+     */
+    pub fn record(&mut self, result: DailyResult) -> bool {
+        if self.results.contains_key(&result.date) {
+            return false;
+        }
+        self.results.insert(result.date.clone(), result);
+        true
+    }
+
+    pub fn get(&self, date: &str) -> Option<&DailyResult> {
+        self.results.get(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::{Col, Row},
+        level::Level,
+        model::{Creeper, Location, Status},
+    };
+
+    use super::{daily_seed, date_key, summary, DailyLog, DailyResult};
+
+    #[test]
+    fn every_day_has_its_own_board() {
+        assert_eq!(date_key(2026, 3, 7), "2026-03-07");
+        assert_eq!(daily_seed(2026, 3, 7), daily_seed(2026, 3, 7));
+        assert_ne!(daily_seed(2026, 3, 7), daily_seed(2026, 3, 8));
+        assert_ne!(daily_seed(2026, 3, 7), daily_seed(2025, 3, 7));
+        // Seeds never change, old summaries keep pointing at the same board.
+        assert_eq!(daily_seed(2026, 1, 1), 0xF2AC_D20E_C0FA_D03A);
+    }
+
+    #[test]
+    fn summaries_show_the_run() {
+        let mut game = Level {
+            map: vec!["F....H", "......", "C....."]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            ..Level::default()
+        }
+        .game(&Default::default())
+        .unwrap();
+        let mut state = game.moves[0].clone();
+        // Ferris heads home, waits, steps back and runs past a creeper that came close.
+        let run = [(1, None), (1, None), (0, None), (1, Some((1, 1)))];
+        let run = run.into_iter().chain((2..=5).map(|x| (x, None)));
+        for (x, creeper) in run {
+            state.ferris.location = Location::at(Row(0), Col(x));
+            if let Some((x, y)) = creeper {
+                state.creepers = vec![Creeper {
+                    location: Location::at(Row(y), Col(x)),
+                }];
+            }
+            game.moves.push(state.clone());
+        }
+        game.status = Status::Won;
+        assert_eq!(
+            summary(&game, "2026-03-07", 12.4),
+            "Escape the creepers 2026-03-07: home in 7 moves, 12s\n🟩🟨🟥🟥🟩🟩🟩🏠"
+        );
+    }
+
+    #[test]
+    fn only_the_first_try_counts() {
+        let mut log = DailyLog::default();
+        let result = |moves| DailyResult {
+            date: "2026-03-07".to_string(),
+            moves,
+            ..DailyResult::default()
+        };
+        assert!(log.record(result(20)));
+        assert!(!log.record(result(10)));
+        assert_eq!(log.get("2026-03-07").unwrap().moves, 20);
+        assert_eq!(DailyLog::from_json(&log.to_json()), log);
+        assert_eq!(DailyLog::from_json("{"), DailyLog::default());
+    }
+}
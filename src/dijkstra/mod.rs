@@ -1,4 +1,4 @@
-use crate::model::{Game, Location, VertexId};
+use crate::model::{Game, Location};
 use anyhow::{anyhow, Result};
 use priority_queue::DoublePriorityQueue;
 use std::{collections::HashMap, hash::Hash};
@@ -6,7 +6,7 @@ use std::{collections::HashMap, hash::Hash};
 #[derive(Debug)]
 pub struct DistanceInfo {
     pub distance: Option<i32>,
-    pub last_vertex: Option<VertexId>,
+    pub last_vertex: Option<Location>,
 }
 
 impl Default for DistanceInfo {
@@ -20,7 +20,7 @@ impl Default for DistanceInfo {
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct VertexInfo {
-    pub vertex: VertexId,
+    pub vertex: Location,
     pub distance: i32,
 }
 
@@ -39,7 +39,8 @@ pub struct Dijkstra {}
 
 impl Dijkstra {
     /**
-     * Computes Dijkstra path using mode
+     * Computes Dijkstra path using mode, fails when `target` can not be reached from
+     * `origin`.
      */
     pub fn run(
         game: &Game,
@@ -52,20 +53,23 @@ impl Dijkstra {
         let mut stack = vec![];
         stack.push(target.clone());
 
-        let mut previous_vertex = distance_table
-            .get(&target.id())
-            .ok_or(anyhow!("cant get item {:?}", &target.id()))?
-            .last_vertex;
+        let target_info = distance_table
+            .get(target)
+            .ok_or(anyhow!("cant get item {:?}", target))?;
+        if target_info.distance.is_none() {
+            return Err(anyhow!("no path from {:?} to {:?}", origin, target));
+        }
+        let mut previous_vertex = target_info.last_vertex.clone();
         while let Some(unwrapped_vertex) = previous_vertex {
-            if unwrapped_vertex == origin.id() {
+            if unwrapped_vertex == *origin {
                 break;
             }
-            let (row, column) = unwrapped_vertex;
-            stack.push(Location { x: row, y: column });
             previous_vertex = distance_table
                 .get(&unwrapped_vertex)
-                .ok_or(anyhow!("cant get item {:?}", &target.id()))?
-                .last_vertex;
+                .ok_or(anyhow!("cant get item {:?}", target))?
+                .last_vertex
+                .clone();
+            stack.push(unwrapped_vertex);
         }
         while let Some(location) = stack.pop() {
             path.push(location);
@@ -78,41 +82,39 @@ impl Dijkstra {
         origin: &Location,
         target: &Location,
         mode: &Mode,
-    ) -> Result<HashMap<VertexId, DistanceInfo>> {
+    ) -> Result<HashMap<Location, DistanceInfo>> {
         // generate all nodes.
-        let mut distance_table: HashMap<VertexId, DistanceInfo> = HashMap::new();
-        let mut vertex_info_map: HashMap<VertexId, VertexInfo> = HashMap::new();
-        let mut queue: DoublePriorityQueue<VertexId, i32> = DoublePriorityQueue::new();
-        for row in 0..game.rows {
-            for column in 0..game.columns {
-                distance_table.insert(Location { x: row, y: column }.id(), DistanceInfo::default());
-            }
+        let mut distance_table: HashMap<Location, DistanceInfo> = HashMap::new();
+        let mut vertex_info_map: HashMap<Location, VertexInfo> = HashMap::new();
+        let mut queue: DoublePriorityQueue<Location, i32> = DoublePriorityQueue::new();
+        for location in game.board().locations() {
+            distance_table.insert(location, DistanceInfo::default());
         }
         let mut origin_distance_info = distance_table
-            .get_mut(&origin.id())
+            .get_mut(origin)
             .ok_or(anyhow!("unable to get element"))?;
         origin_distance_info.distance = Some(0);
-        origin_distance_info.last_vertex = Some(origin.id());
+        origin_distance_info.last_vertex = Some(origin.clone());
 
         let source_vertex_info = VertexInfo {
-            vertex: origin.id(),
+            vertex: origin.clone(),
             distance: 0,
         };
-        vertex_info_map.insert(origin.id(), source_vertex_info.clone());
+        vertex_info_map.insert(origin.clone(), source_vertex_info.clone());
         queue.push(source_vertex_info.vertex, source_vertex_info.distance);
 
         while let Some(vertex_info) = queue.pop_min() {
             let current_vertex = vertex_info.0;
-            for neighbor in game.get_adjacent_vertices(current_vertex, &target, mode) {
+            for neighbor in game.get_adjacent_vertices(&current_vertex, target, mode) {
                 // Get the new distance, account for the weighted edge.
                 let distance = distance_table
                     .get(&neighbor)
                     .ok_or(anyhow!("get neighbor failed"))?
                     .distance
                     .map(|distance| {
-                        distance + game.get_weighted_edge(current_vertex, neighbor, target, mode)
+                        distance + game.get_weighted_edge(&current_vertex, &neighbor, target, mode)
                     })
-                    .unwrap_or(game.get_weighted_edge(current_vertex, neighbor, target, mode));
+                    .unwrap_or(game.get_weighted_edge(&current_vertex, &neighbor, target, mode));
 
                 // If we find a new shortest path to the neighbor, update
                 // the distance and the last vertex.
@@ -126,16 +128,16 @@ impl Dijkstra {
                         .get_mut(&neighbor)
                         .ok_or(anyhow!("get neighbor failed"))?;
                     neighbor_vertex.distance = Some(distance);
-                    neighbor_vertex.last_vertex = Some(current_vertex);
+                    neighbor_vertex.last_vertex = Some(current_vertex.clone());
 
                     if let Some(neighbor_vertex_info) = vertex_info_map.get(&neighbor) {
                         queue.remove(&neighbor_vertex_info.vertex);
                     }
                     let vertex_info = VertexInfo {
-                        vertex: neighbor,
+                        vertex: neighbor.clone(),
                         distance,
                     };
-                    queue.push(vertex_info.vertex, vertex_info.distance);
+                    queue.push(vertex_info.vertex.clone(), vertex_info.distance);
                     vertex_info_map.insert(neighbor, vertex_info);
                 }
             }
@@ -148,8 +150,9 @@ impl Dijkstra {
 #[cfg(test)]
 mod tests {
     use crate::{
+        board::{Col, Row},
         dijkstra::Mode,
-        model::{Creeper, Game, GameState, Location, Status},
+        model::{Connectivity, Creeper, Game, GameState, Location},
     };
 
     use super::Dijkstra;
@@ -160,22 +163,20 @@ mod tests {
             moves: vec![GameState {
                 creepers: vec![],
                 ferris: crate::model::Ferris {
-                    location: Location { x: 0, y: 0 },
+                    location: Location::at(Row(0), Col(0)),
                     path: vec![],
                 },
             }],
-            rows: 4,
-            columns: 4,
-            target: Location { x: 0, y: 3 },
-            status: Status::Idle,
+            target: Location::at(Row(3), Col(0)),
+            ..Game::empty(4, 4)
         };
         let origin = &game.moves.last().unwrap().ferris.location;
         let target = &game.target;
         let shortest_path = Dijkstra::run(&game, origin, target, &Mode::Ferris).unwrap();
         let expected_shortest_path = vec![
-            Location { x: 0, y: 1 },
-            Location { x: 0, y: 2 },
-            Location { x: 0, y: 3 },
+            Location::at(Row(1), Col(0)),
+            Location::at(Row(2), Col(0)),
+            Location::at(Row(3), Col(0)),
         ];
         assert_eq!(shortest_path, expected_shortest_path);
     }
@@ -186,22 +187,20 @@ mod tests {
             moves: vec![GameState {
                 creepers: vec![],
                 ferris: crate::model::Ferris {
-                    location: Location { x: 0, y: 0 },
+                    location: Location::at(Row(0), Col(0)),
                     path: vec![],
                 },
             }],
-            rows: 4,
-            columns: 4,
-            target: Location { x: 3, y: 3 },
-            status: Status::Idle,
+            target: Location::at(Row(3), Col(3)),
+            ..Game::empty(4, 4)
         };
         let origin = &game.moves.last().unwrap().ferris.location;
         let target = &game.target;
         let shortest_path = Dijkstra::run(&game, origin, target, &Mode::Ferris).unwrap();
         let expected_shortest_path = vec![
-            Location { x: 1, y: 1 },
-            Location { x: 2, y: 2 },
-            Location { x: 3, y: 3 },
+            Location::at(Row(1), Col(1)),
+            Location::at(Row(2), Col(2)),
+            Location::at(Row(3), Col(3)),
         ];
         assert_eq!(shortest_path, expected_shortest_path);
     }
@@ -212,24 +211,22 @@ mod tests {
             moves: vec![GameState {
                 creepers: vec![],
                 ferris: crate::model::Ferris {
-                    location: Location { x: 2, y: 2 },
+                    location: Location::at(Row(2), Col(2)),
                     path: vec![],
                 },
             }],
-            rows: 8,
-            columns: 8,
-            target: Location { x: 7, y: 7 },
-            status: Status::Idle,
+            target: Location::at(Row(7), Col(7)),
+            ..Game::empty(8, 8)
         };
         let origin = &game.moves.last().unwrap().ferris.location;
         let target = &game.target;
         let shortest_path = Dijkstra::run(&game, origin, target, &Mode::Ferris).unwrap();
         let expected_shortest_path = vec![
-            Location { x: 3, y: 3 },
-            Location { x: 4, y: 4 },
-            Location { x: 5, y: 5 },
-            Location { x: 6, y: 6 },
-            Location { x: 7, y: 7 },
+            Location::at(Row(3), Col(3)),
+            Location::at(Row(4), Col(4)),
+            Location::at(Row(5), Col(5)),
+            Location::at(Row(6), Col(6)),
+            Location::at(Row(7), Col(7)),
         ];
         assert_eq!(shortest_path, expected_shortest_path);
     }
@@ -239,31 +236,29 @@ mod tests {
         let game = Game {
             moves: vec![GameState {
                 creepers: vec![Creeper {
-                    location: Location { x: 4, y: 4 },
+                    location: Location::at(Row(4), Col(4)),
                 }],
                 ferris: crate::model::Ferris {
-                    location: Location { x: 2, y: 2 },
+                    location: Location::at(Row(2), Col(2)),
                     path: vec![],
                 },
             }],
-            rows: 8,
-            columns: 8,
-            target: Location { x: 7, y: 7 },
-            status: Status::Idle,
+            target: Location::at(Row(7), Col(7)),
+            ..Game::empty(8, 8)
         };
         let ferris_location = &game.moves.last().unwrap().ferris.location;
         let target = &game.target;
         let shortest_path = Dijkstra::run(&game, ferris_location, target, &Mode::Ferris).unwrap();
         let expected_shortest_path = vec![
-            Location { x: 3, y: 1 },
-            Location { x: 4, y: 1 },
-            Location { x: 5, y: 1 },
-            Location { x: 6, y: 2 },
-            Location { x: 7, y: 3 },
-            Location { x: 7, y: 4 },
-            Location { x: 7, y: 5 },
-            Location { x: 7, y: 6 },
-            Location { x: 7, y: 7 },
+            Location::at(Row(1), Col(3)),
+            Location::at(Row(1), Col(4)),
+            Location::at(Row(1), Col(5)),
+            Location::at(Row(2), Col(6)),
+            Location::at(Row(3), Col(7)),
+            Location::at(Row(4), Col(7)),
+            Location::at(Row(5), Col(7)),
+            Location::at(Row(6), Col(7)),
+            Location::at(Row(7), Col(7)),
         ];
 
         assert_eq!(shortest_path, expected_shortest_path);
@@ -283,7 +278,7 @@ mod tests {
         .unwrap();
         assert_eq!(
             creeper_path,
-            vec![Location { x: 3, y: 3 }, Location { x: 2, y: 2 }]
+            vec![Location::at(Row(3), Col(3)), Location::at(Row(2), Col(2))]
         );
     }
 
@@ -292,25 +287,23 @@ mod tests {
         let game = Game {
             moves: vec![GameState {
                 creepers: vec![Creeper {
-                    location: Location { x: 3, y: 3 },
+                    location: Location::at(Row(3), Col(3)),
                 }],
                 ferris: crate::model::Ferris {
-                    location: Location { x: 0, y: 0 },
+                    location: Location::at(Row(0), Col(0)),
                     path: vec![],
                 },
             }],
-            rows: 4,
-            columns: 4,
-            target: Location { x: 3, y: 3 },
-            status: Status::Idle,
+            target: Location::at(Row(3), Col(3)),
+            ..Game::empty(4, 4)
         };
         let origin = &game.moves.last().unwrap().ferris.location;
         let target = &game.target;
         let shortest_path = Dijkstra::run(&game, origin, target, &Mode::Ferris).unwrap();
         let expected_shortest_path = vec![
-            Location { x: 1, y: 1 },
-            Location { x: 2, y: 2 },
-            Location { x: 3, y: 3 },
+            Location::at(Row(1), Col(1)),
+            Location::at(Row(2), Col(2)),
+            Location::at(Row(3), Col(3)),
         ];
         assert_eq!(shortest_path, expected_shortest_path);
     }
@@ -320,22 +313,84 @@ mod tests {
         let game = Game {
             moves: vec![GameState {
                 creepers: vec![Creeper {
-                    location: Location { x: 0, y: 9 },
+                    location: Location::at(Row(9), Col(0)),
                 }],
                 ferris: crate::model::Ferris {
-                    location: Location { x: 4, y: 3 },
+                    location: Location::at(Row(3), Col(4)),
                     path: vec![],
                 },
             }],
-            rows: 24,
-            columns: 12,
-            target: Location { x: 5, y: 5 },
-            status: Status::Idle,
+            target: Location::at(Row(5), Col(5)),
+            ..Game::empty(12, 24)
         };
         let origin = &game.moves.last().unwrap().ferris.location;
         let target = &game.target;
         let shortest_path = Dijkstra::run(&game, origin, target, &Mode::Creeper).unwrap();
-        let expected_shortest_path = vec![Location { x: 5, y: 4 }, Location { x: 5, y: 5 }];
+        let expected_shortest_path =
+            vec![Location::at(Row(4), Col(5)), Location::at(Row(5), Col(5))];
         assert_eq!(shortest_path, expected_shortest_path);
     }
+
+    #[test]
+    fn dijkstra_through_wrapped_edges() {
+        let mut game = Game {
+            moves: vec![GameState {
+                creepers: vec![],
+                ferris: crate::model::Ferris {
+                    location: Location::at(Row(5), Col(0)),
+                    path: vec![],
+                },
+            }],
+            target: Location::at(Row(5), Col(8)),
+            connectivity: Connectivity::Four,
+            wrap: true,
+            ..Game::empty(10, 10)
+        };
+        let origin = game.moves.last().unwrap().ferris.location.clone();
+        let shortest_path = Dijkstra::run(&game, &origin, &game.target, &Mode::Ferris).unwrap();
+        assert_eq!(
+            shortest_path,
+            vec![Location::at(Row(5), Col(9)), Location::at(Row(5), Col(8))]
+        );
+
+        game.wrap = false;
+        let shortest_path = Dijkstra::run(&game, &origin, &game.target, &Mode::Ferris).unwrap();
+        assert_eq!(shortest_path.len(), 8);
+    }
+
+    #[test]
+    fn dijkstra_unreachable_target() {
+        // A creeper stands in the only gap of a wall between Ferris and home.
+        let mut game = Game {
+            moves: vec![GameState {
+                creepers: vec![Creeper {
+                    location: Location::at(Row(1), Col(1)),
+                }],
+                ferris: crate::model::Ferris {
+                    location: Location::at(Row(0), Col(0)),
+                    path: vec![],
+                },
+            }],
+            target: Location::at(Row(2), Col(0)),
+            connectivity: Connectivity::Four,
+            walls: [Location::at(Row(1), Col(0)), Location::at(Row(1), Col(2))]
+                .into_iter()
+                .collect(),
+            ..Game::empty(3, 3)
+        };
+        let origin = game.moves.last().unwrap().ferris.location.clone();
+        assert!(Dijkstra::run(&game, &origin, &game.target, &Mode::Ferris).is_err());
+        // Ferris still heads home, past the creeper rather than through a wall.
+        assert_eq!(
+            game.path_home(&origin),
+            vec![
+                Location::at(Row(0), Col(1)),
+                Location::at(Row(1), Col(1)),
+                Location::at(Row(2), Col(1)),
+                Location::at(Row(2), Col(0)),
+            ]
+        );
+        game.walls.insert(Location::at(Row(1), Col(1)));
+        assert_eq!(game.path_home(&origin), vec![]);
+    }
 }
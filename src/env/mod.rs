@@ -0,0 +1,449 @@
+use crate::hunt::steps_between;
+use crate::model::{Direction, Game, GameConfig, Location, Status};
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+
+/**
+ * Layers of an observation, every channel is a rows x columns grid with a 1 on the
+ * cells that hold the thing and 0 everywhere else.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Channel {
+    Ferris = 0,
+    Creepers = 1,
+    Home = 2,
+    Walls = 3,
+}
+
+pub const CHANNELS: usize = 4;
+
+/**
+ * What the agent sees after every step, a channels x rows x columns tensor stored
+ * channel first, row major.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observation {
+    pub rows: usize,
+    pub columns: usize,
+    pub data: Vec<f32>,
+}
+
+impl Observation {
+    fn empty(rows: usize, columns: usize) -> Observation {
+        Observation {
+            rows,
+            columns,
+            data: vec![0f32; CHANNELS * rows * columns],
+        }
+    }
+
+    /**
+     * Encodes the last state of `game`.
+     */
+    pub fn from_game(game: &Game) -> Observation {
+        let mut observation = Observation::empty(game.rows as usize, game.columns as usize);
+        if let Some(state) = game.moves.last() {
+            observation.set(Channel::Ferris, &state.ferris.location);
+            for creeper in &state.creepers {
+                observation.set(Channel::Creepers, &creeper.location);
+            }
+        }
+        observation.set(Channel::Home, &game.target);
+        for wall in &game.walls {
+            observation.set(Channel::Walls, wall);
+        }
+        observation
+    }
+
+    pub fn shape(&self) -> [usize; 3] {
+        [CHANNELS, self.rows, self.columns]
+    }
+
+    pub fn index(&self, channel: Channel, row: usize, column: usize) -> usize {
+        (channel as usize * self.rows + row) * self.columns + column
+    }
+
+    pub fn get(&self, channel: Channel, row: usize, column: usize) -> f32 {
+        self.data[self.index(channel, row, column)]
+    }
+
+    fn set(&mut self, channel: Channel, location: &Location) {
+        let (row, column) = match (
+            usize::try_from(location.row().0),
+            usize::try_from(location.col().0),
+        ) {
+            (Ok(row), Ok(column)) => (row, column),
+            _ => return,
+        };
+        if row < self.rows && column < self.columns {
+            let index = self.index(channel, row, column);
+            self.data[index] = 1f32;
+        }
+    }
+}
+
+/**
+ * What the agent can do on every step. Staying put lets the creepers come closer.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Stay,
+    Move(Direction),
+}
+
+impl Action {
+    /**
+     * Every action, the index of an action in this list is its id for agents that
+     * work with discrete action spaces.
+     */
+    pub fn all() -> Vec<Action> {
+        vec![
+            Action::Stay,
+            Action::Move(Direction::Up),
+            Action::Move(Direction::Down),
+            Action::Move(Direction::Left),
+            Action::Move(Direction::Right),
+            // Diagonal moves came later, they go last to keep the ids of the others.
+            Action::Move(Direction::UpLeft),
+            Action::Move(Direction::UpRight),
+            Action::Move(Direction::DownLeft),
+            Action::Move(Direction::DownRight),
+        ]
+    }
+
+    pub fn from_index(index: usize) -> Option<Action> {
+        Action::all().get(index).copied()
+    }
+}
+
+/**
+ * Reward shaping, every field is added to the reward of a step when it applies.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct RewardConfig {
+    // Ferris got home.
+    pub win: f32,
+    // A creeper got Ferris.
+    pub loss: f32,
+    // Paid on every step, use a negative value to rush the agent.
+    pub step: f32,
+    // Paid per move Ferris got closer to home, taken away when Ferris moves away.
+    pub progress: f32,
+    // Paid when a creeper ends the step next to Ferris.
+    pub danger: f32,
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        RewardConfig {
+            win: 1.0,
+            loss: -1.0,
+            step: -0.01,
+            progress: 0.05,
+            danger: -0.05,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvConfig {
+    pub game: GameConfig,
+    pub rewards: RewardConfig,
+    // The episode ends after this many steps even if the game is still going.
+    pub max_steps: i16,
+}
+
+/**
+ * Extra details about a step that are not part of the observation.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepInfo {
+    pub tick: i16,
+    pub status: Status,
+    // The game is still going but the episode ran out of steps.
+    pub truncated: bool,
+    // Moves between Ferris and the closest creeper.
+    pub closest_creeper: Option<i32>,
+}
+
+/**
+ * Gym style environment to train agents on the game without a browser. Every step
+ * is one tick of the game: creepers move on their ticks and then Ferris follows the
+ * action instead of the autopilot.
+ */
+pub struct Env {
+    config: EnvConfig,
+    game: Game,
+    tick: i16,
+}
+
+impl Env {
+    /**
+     * Creates the environment with the board of seed 0, call `reset` to pick another.
+     */
+    pub fn new(config: EnvConfig) -> Env {
+        Env {
+            game: Env::spawn(&config.game, 0),
+            config,
+            tick: 0,
+        }
+    }
+
+    /**
+     * Starts a new episode, the same seed always spawns the same board.
+     */
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.game = Env::spawn(&self.config.game, seed);
+        self.tick = 0;
+        self.observation()
+    }
+
+    fn spawn(config: &GameConfig, seed: u64) -> Game {
+        let mut game = Game::new(config, &mut StdRng::seed_from_u64(seed));
+        if game.status == Status::Idle {
+            game.status = Status::Playing;
+        }
+        game
+    }
+
+    /**
+     * Plays one tick with `action`, returns the new observation, the reward, whether the
+     * episode is over and extra details.
+     */
+    pub fn step(&mut self, action: &Action) -> (Observation, f32, bool, StepInfo) {
+        if self.is_done() {
+            return (self.observation(), 0f32, true, self.info());
+        }
+        // Walls and home stay put during a step, one map of the moves home does.
+        let home_steps = self.game.steps_to(&self.game.target);
+        let home_distance_before = self.home_distance(&home_steps);
+        self.tick += 1;
+        let mut game = self.game.creepers_turn(self.tick);
+        let ferris_location = game.moves.last().unwrap().ferris.location.clone();
+        let ferris_location = match action {
+            Action::Move(direction) if game.connectivity.allows(*direction) => {
+                game.step_ferris(&ferris_location, *direction)
+            }
+            // Diagonal moves on four-connected boards leave Ferris where it is.
+            _ => ferris_location,
+        };
+        game.moves.last_mut().unwrap().ferris.location = ferris_location.clone();
+        // Keep Ferris's path up to date, creepers hunting in packs rely on it.
+        game.moves.last_mut().unwrap().ferris.path = game.path_home(&ferris_location);
+        game.validate_status();
+        self.game = game;
+
+        let rewards = &self.config.rewards;
+        let mut reward = rewards.step;
+        reward +=
+            rewards.progress * (home_distance_before - self.home_distance(&home_steps)) as f32;
+        match self.game.status {
+            Status::Won => reward += rewards.win,
+            Status::Lost => reward += rewards.loss,
+            _ => {
+                if self.closest_creeper() == Some(1) {
+                    reward += rewards.danger;
+                }
+            }
+        }
+        (self.observation(), reward, self.is_done(), self.info())
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn observation(&self) -> Observation {
+        Observation::from_game(&self.game)
+    }
+
+    fn is_done(&self) -> bool {
+        self.game.status != Status::Playing || self.tick >= self.config.max_steps
+    }
+
+    fn info(&self) -> StepInfo {
+        StepInfo {
+            tick: self.tick,
+            status: self.game.status.clone(),
+            truncated: self.game.status == Status::Playing && self.tick >= self.config.max_steps,
+            closest_creeper: self.closest_creeper(),
+        }
+    }
+
+    /**
+     * Moves Ferris needs to get home around the walls, `home_steps` comes from
+     * `Game::steps_to`.
+     */
+    fn home_distance(&self, home_steps: &HashMap<Location, i32>) -> i32 {
+        self.game
+            .moves
+            .last()
+            .and_then(|state| home_steps.get(&state.ferris.location).copied())
+            .unwrap_or(self.game.rows * self.game.columns)
+    }
+
+    fn closest_creeper(&self) -> Option<i32> {
+        let state = self.game.moves.last()?;
+        state
+            .creepers
+            .iter()
+            .map(|creeper| {
+                steps_between(
+                    &creeper.location,
+                    &state.ferris.location,
+                    &self.game.board(),
+                )
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        autopilot::Autopilot,
+        board::{Col, Row},
+        hunt::CreeperStrategy,
+        maze::Maze,
+        model::{Connectivity, Direction, GameConfig, Location, Status},
+    };
+
+    use super::{Action, Channel, Env, EnvConfig, RewardConfig, CHANNELS};
+
+    fn config() -> EnvConfig {
+        EnvConfig {
+            game: GameConfig {
+                creepers: 3,
+                rows: 5,
+                columns: 6,
+                creeper_strategy: CreeperStrategy::Chase,
+                autopilot: Autopilot::Greedy,
+                connectivity: Connectivity::Eight,
+                wrap: false,
+                maze: Maze::Open,
+            },
+            rewards: RewardConfig::default(),
+            max_steps: 50,
+        }
+    }
+
+    #[test]
+    fn reset_is_deterministic() {
+        let mut env = Env::new(config());
+        let first = env.reset(42);
+        let second = env.reset(42);
+        assert_eq!(first, second);
+        assert_eq!(first.shape(), [CHANNELS, 5, 6]);
+        assert_eq!(env.game().status, Status::Playing);
+    }
+
+    #[test]
+    fn observation_channels() {
+        let mut env = Env::new(config());
+        let observation = env.reset(3);
+        let state = env.game().moves.last().unwrap().clone();
+        let sum = |channel: Channel| -> f32 {
+            (0..5)
+                .flat_map(|row| (0..6).map(move |column| (row, column)))
+                .map(|(row, column)| observation.get(channel, row, column))
+                .sum()
+        };
+        assert_eq!(sum(Channel::Ferris), 1f32);
+        assert_eq!(sum(Channel::Creepers), 3f32);
+        assert_eq!(sum(Channel::Home), 1f32);
+        assert_eq!(sum(Channel::Walls), 0f32);
+        let ferris = &state.ferris.location;
+        assert_eq!(
+            observation.get(
+                Channel::Ferris,
+                ferris.row().0 as usize,
+                ferris.col().0 as usize
+            ),
+            1f32
+        );
+    }
+
+    #[test]
+    fn observation_walls() {
+        let mut config = config();
+        config.game.maze = Maze::Backtracker;
+        let mut env = Env::new(config);
+        let observation = env.reset(5);
+        let game = env.game();
+        assert!(!game.walls.is_empty());
+        for row in 0..5 {
+            for column in 0..6 {
+                let wall = Location::at(Row(row), Col(column));
+                assert_eq!(
+                    observation.get(Channel::Walls, row as usize, column as usize),
+                    if game.walls.contains(&wall) {
+                        1f32
+                    } else {
+                        0f32
+                    }
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn step_rewards_getting_home() {
+        let mut env = Env::new(config());
+        env.reset(0);
+        // Put Ferris right next to home, away from creepers.
+        let mut game = env.game().clone();
+        let state = game.moves.last_mut().unwrap();
+        state.creepers.clear();
+        state.ferris.location = Location::at(Row(2), Col(2));
+        game.target = Location::at(Row(2), Col(3));
+        env.game = game;
+
+        let (_, reward, done, info) = env.step(&Action::Move(Direction::Right));
+        assert!(done);
+        assert_eq!(info.status, Status::Won);
+        let rewards = RewardConfig::default();
+        assert_eq!(reward, rewards.step + rewards.progress + rewards.win);
+
+        // Once the episode is over steps do nothing.
+        let (_, reward, done, _) = env.step(&Action::Stay);
+        assert!(done);
+        assert_eq!(reward, 0f32);
+    }
+
+    #[test]
+    fn progress_goes_around_walls() {
+        let mut env = Env::new(config());
+        env.reset(0);
+        // Home is right behind a wall, the way there starts by walking away from it.
+        let mut game = env.game().clone();
+        let state = game.moves.last_mut().unwrap();
+        state.creepers.clear();
+        state.ferris.location = Location::at(Row(0), Col(0));
+        game.target = Location::at(Row(0), Col(2));
+        game.connectivity = Connectivity::Four;
+        game.walls = (0..4).map(|row| Location::at(Row(row), Col(1))).collect();
+        env.game = game;
+
+        let (_, reward, _, _) = env.step(&Action::Move(Direction::Down));
+        let rewards = RewardConfig::default();
+        assert_eq!(reward, rewards.step + rewards.progress);
+    }
+
+    #[test]
+    fn episodes_are_truncated() {
+        let mut config = config();
+        config.game.creepers = 0;
+        config.max_steps = 3;
+        let mut env = Env::new(config);
+        env.reset(1);
+        let mut result = env.step(&Action::Stay);
+        while !result.2 {
+            result = env.step(&Action::Stay);
+        }
+        let info = result.3;
+        assert_eq!(info.tick, 3);
+        assert!(info.truncated);
+        assert_eq!(Action::from_index(0), Some(Action::Stay));
+        assert_eq!(Action::from_index(Action::all().len()), None);
+    }
+}
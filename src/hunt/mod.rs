@@ -0,0 +1,239 @@
+use crate::board::Board;
+use crate::model::{GameState, Location};
+use anyhow::anyhow;
+use std::{collections::HashSet, fmt, str::FromStr};
+
+/**
+ * Creepers only move every other tick, Ferris moves every tick.
+ */
+pub const TICKS_PER_CREEPER_MOVE: i32 = 2;
+
+/**
+ * How creepers decide where to go.
+ * Use Chase to send every creeper down the shortest path to Ferris.
+ * Use Pack to have the closest creeper chase Ferris while the rest of the pack
+ * cuts off Ferris's way home.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum CreeperStrategy {
+    #[default]
+    Chase,
+    Pack,
+}
+
+impl FromStr for CreeperStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(strategy: &str) -> Result<Self, Self::Err> {
+        match strategy.to_lowercase().as_str() {
+            "chase" => Ok(CreeperStrategy::Chase),
+            "pack" => Ok(CreeperStrategy::Pack),
+            _ => Err(anyhow!(
+                "unknown creeper strategy {:?}, use chase or pack",
+                strategy
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CreeperStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CreeperStrategy::Chase => write!(f, "chase"),
+            CreeperStrategy::Pack => write!(f, "pack"),
+        }
+    }
+}
+
+/**
+ * Number of moves needed to walk from one location to another on an open board.
+ */
+pub fn steps_between(from: &Location, to: &Location, board: &Board) -> i32 {
+    board.steps(from, to)
+}
+
+/**
+ * Picks the location every creeper walks towards this turn.
+ *
+ * `strategies` has the strategy of every creeper and `order` lists the creepers from
+ * closest to furthest from Ferris. The first creeper keeps chasing Ferris whatever its
+ * strategy, the other Pack creepers are assigned to the earliest cell of Ferris's path
+ * home that they can reach before Ferris does, no two creepers share an intercept
+ * point. Creepers that can not get ahead of Ferris guard the spot of the path they are
+ * least late to, and creepers left without a spot chase.
+ */
+pub fn assign_goals(
+    state: &GameState,
+    target: &Location,
+    strategies: &[CreeperStrategy],
+    board: &Board,
+    order: &[usize],
+) -> Vec<Location> {
+    let ferris_location = &state.ferris.location;
+    let mut goals = vec![ferris_location.clone(); state.creepers.len()];
+    let pack: Vec<usize> = order
+        .iter()
+        .skip(1)
+        .copied()
+        .filter(|index| strategies.get(*index) == Some(&CreeperStrategy::Pack))
+        .collect();
+    if pack.is_empty() {
+        return goals;
+    }
+
+    // Ferris's escape route, the cell at index k is reached after k + 1 ticks.
+    let mut route = state.ferris.path.clone();
+    if route.last() != Some(target) {
+        route.push(target.clone());
+    }
+    let mut assigned: HashSet<usize> = HashSet::new();
+    for index in pack {
+        let creeper_location = &state.creepers[index].location;
+        // How many ticks the creeper arrives after Ferris, negative means ahead of Ferris.
+        let delay = |k: usize| {
+            steps_between(creeper_location, &route[k], board) * TICKS_PER_CREEPER_MOVE
+                - (k as i32 + 1)
+        };
+        let open_spots = || (0..route.len()).filter(|k| !assigned.contains(k));
+        let intercept = open_spots()
+            .find(|k| delay(*k) <= 0)
+            .or_else(|| open_spots().min_by_key(|k| delay(*k)));
+        if let Some(k) = intercept {
+            assigned.insert(k);
+            goals[index] = route[k].clone();
+        }
+    }
+    goals
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::{Board, Col, Row},
+        model::{Connectivity, Creeper, Ferris, GameState, Location},
+    };
+
+    use super::{assign_goals, CreeperStrategy};
+
+    fn state_with_creepers(creepers: Vec<Location>) -> GameState {
+        GameState {
+            creepers: creepers
+                .into_iter()
+                .map(|location| Creeper { location })
+                .collect(),
+            ferris: Ferris {
+                location: Location::at(Row(0), Col(0)),
+                path: vec![
+                    Location::at(Row(1), Col(0)),
+                    Location::at(Row(2), Col(0)),
+                    Location::at(Row(3), Col(0)),
+                    Location::at(Row(4), Col(0)),
+                    Location::at(Row(5), Col(0)),
+                    Location::at(Row(6), Col(0)),
+                ],
+            },
+        }
+    }
+
+    #[test]
+    fn assign_goals_chase() {
+        let state = state_with_creepers(vec![
+            Location::at(Row(3), Col(3)),
+            Location::at(Row(5), Col(5)),
+        ]);
+        let target = Location::at(Row(6), Col(0));
+        let goals = assign_goals(
+            &state,
+            &target,
+            &[CreeperStrategy::Chase, CreeperStrategy::Chase],
+            &Board::new(10, 10, Connectivity::Eight),
+            &[0, 1],
+        );
+        assert_eq!(
+            goals,
+            vec![Location::at(Row(0), Col(0)), Location::at(Row(0), Col(0))]
+        );
+    }
+
+    #[test]
+    fn assign_goals_pack_intercepts_path() {
+        let state = state_with_creepers(vec![
+            Location::at(Row(0), Col(2)),
+            Location::at(Row(4), Col(2)),
+            Location::at(Row(5), Col(2)),
+        ]);
+        let target = Location::at(Row(6), Col(0));
+        let goals = assign_goals(
+            &state,
+            &target,
+            &[
+                CreeperStrategy::Pack,
+                CreeperStrategy::Pack,
+                CreeperStrategy::Pack,
+            ],
+            &Board::new(10, 10, Connectivity::Eight),
+            &[0, 1, 2],
+        );
+        assert_eq!(
+            goals,
+            vec![
+                // the closest creeper chases Ferris.
+                Location::at(Row(0), Col(0)),
+                // two moves away, it gets to (0, 4) on tick 4, right as Ferris does.
+                Location::at(Row(4), Col(0)),
+                // (0, 4) is taken, the next spot it can reach in time is (0, 5).
+                Location::at(Row(5), Col(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn assign_goals_pack_guards_home_when_late() {
+        let state = state_with_creepers(vec![
+            Location::at(Row(0), Col(1)),
+            Location::at(Row(9), Col(9)),
+        ]);
+        let target = Location::at(Row(6), Col(0));
+        let goals = assign_goals(
+            &state,
+            &target,
+            &[CreeperStrategy::Pack, CreeperStrategy::Pack],
+            &Board::new(10, 10, Connectivity::Eight),
+            &[0, 1],
+        );
+        assert_eq!(
+            goals,
+            vec![Location::at(Row(0), Col(0)), Location::at(Row(6), Col(0))]
+        );
+    }
+
+    #[test]
+    fn assign_goals_mixed_strategies() {
+        let state = state_with_creepers(vec![
+            Location::at(Row(0), Col(2)),
+            Location::at(Row(4), Col(2)),
+            Location::at(Row(5), Col(2)),
+        ]);
+        let target = Location::at(Row(6), Col(0));
+        let goals = assign_goals(
+            &state,
+            &target,
+            &[
+                CreeperStrategy::Pack,
+                CreeperStrategy::Chase,
+                CreeperStrategy::Pack,
+            ],
+            &Board::new(10, 10, Connectivity::Eight),
+            &[0, 1, 2],
+        );
+        assert_eq!(
+            goals,
+            vec![
+                Location::at(Row(0), Col(0)),
+                // a chaser, it does not cut Ferris off even with spots left on the path.
+                Location::at(Row(0), Col(0)),
+                Location::at(Row(4), Col(0)),
+            ]
+        );
+    }
+}
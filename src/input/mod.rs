@@ -0,0 +1,245 @@
+use crate::model::Direction;
+
+// Buttons of the standard gamepad mapping, see https://w3c.github.io/gamepad/#remapping
+const BUTTON_START: usize = 9;
+const BUTTON_UP: usize = 12;
+const BUTTON_DOWN: usize = 13;
+const BUTTON_LEFT: usize = 14;
+const BUTTON_RIGHT: usize = 15;
+
+/**
+ * How far the analog stick has to be pushed before it counts as a direction.
+ */
+pub const STICK_DEAD_ZONE: f64 = 0.5;
+
+/**
+ * Direction bound to a key, `key` and `code` are the ones of a `KeyboardEvent`.
+ *
+ * Arrows move straight and the QWE/ASD/ZXC block moves like the numpad, S moves down
+ * too for players used to WASD. Numpad keys are matched by `code` so they work with
+ * num lock on or off.
+ */
+pub fn key_direction(key: &str, code: &str) -> Option<Direction> {
+    let numpad = match code {
+        "Numpad7" => Some(Direction::UpLeft),
+        "Numpad8" => Some(Direction::Up),
+        "Numpad9" => Some(Direction::UpRight),
+        "Numpad4" => Some(Direction::Left),
+        "Numpad6" => Some(Direction::Right),
+        "Numpad1" => Some(Direction::DownLeft),
+        "Numpad2" => Some(Direction::Down),
+        "Numpad3" => Some(Direction::DownRight),
+        _ => None,
+    };
+    if numpad.is_some() {
+        return numpad;
+    }
+    match key.to_lowercase().as_str() {
+        "arrowup" | "w" => Some(Direction::Up),
+        "arrowdown" | "s" | "x" => Some(Direction::Down),
+        "arrowleft" | "a" => Some(Direction::Left),
+        "arrowright" | "d" => Some(Direction::Right),
+        "q" => Some(Direction::UpLeft),
+        "e" => Some(Direction::UpRight),
+        "z" => Some(Direction::DownLeft),
+        "c" => Some(Direction::DownRight),
+        _ => None,
+    }
+}
+
+/**
+ * Snapshot of a gamepad, laid out like the standard mapping of the Gamepad API.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GamepadState {
+    // Left stick is axes 0 (left -1 to right 1) and 1 (up -1 to down 1).
+    pub axes: Vec<f64>,
+    pub buttons: Vec<bool>,
+}
+
+impl GamepadState {
+    fn pressed(&self, button: usize) -> bool {
+        self.buttons.get(button).copied().unwrap_or(false)
+    }
+
+    fn axis(&self, axis: usize) -> f64 {
+        self.axes.get(axis).copied().unwrap_or(0f64)
+    }
+
+    /**
+     * Direction held on the D-pad, or on the left stick when the D-pad is not pressed.
+     */
+    pub fn direction(&self) -> Option<Direction> {
+        let d_pad = [
+            (BUTTON_UP, Direction::Up),
+            (BUTTON_DOWN, Direction::Down),
+            (BUTTON_LEFT, Direction::Left),
+            (BUTTON_RIGHT, Direction::Right),
+        ]
+        .into_iter()
+        .find(|(button, _direction)| self.pressed(*button))
+        .map(|(_button, direction)| direction);
+        if d_pad.is_some() {
+            return d_pad;
+        }
+        let (x, y) = (self.axis(0), self.axis(1));
+        if x.abs().max(y.abs()) < STICK_DEAD_ZONE {
+            None
+        } else if x.abs() > y.abs() {
+            Some(if x > 0f64 {
+                Direction::Right
+            } else {
+                Direction::Left
+            })
+        } else if y > 0f64 {
+            Some(Direction::Down)
+        } else {
+            Some(Direction::Up)
+        }
+    }
+}
+
+/**
+ * What the player asked for with the gamepad.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameInput {
+    Move(Direction),
+    // Pauses or resumes a game, starts a new one when it is over.
+    Start,
+}
+
+/**
+ * Anything that can be asked for the state of a gamepad, the browser in the web app
+ * and canned states in tests.
+ */
+pub trait GamepadSource {
+    // None when no gamepad is connected.
+    fn poll(&mut self) -> Option<GamepadState>;
+}
+
+/**
+ * Turns gamepad snapshots into inputs. Inputs fire when a direction or button is
+ * first pressed, holding it does not repeat the input.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct GamepadMapper {
+    direction: Option<Direction>,
+    start: bool,
+}
+
+impl GamepadMapper {
+    pub fn update(&mut self, state: &GamepadState) -> Vec<GameInput> {
+        let mut inputs = vec![];
+        let direction = state.direction();
+        if let Some(direction) = direction.filter(|direction| Some(*direction) != self.direction) {
+            inputs.push(GameInput::Move(direction));
+        }
+        self.direction = direction;
+        let start = state.pressed(BUTTON_START);
+        if start && !self.start {
+            inputs.push(GameInput::Start);
+        }
+        self.start = start;
+        inputs
+    }
+
+    /**
+     * Polls `source` once, a gamepad that goes away releases everything.
+     */
+    pub fn poll<S: GamepadSource>(&mut self, source: &mut S) -> Vec<GameInput> {
+        let state = source.poll().unwrap_or_default();
+        self.update(&state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::Direction;
+
+    use super::{key_direction, GameInput, GamepadMapper, GamepadSource, GamepadState};
+
+    struct MockGamepad {
+        states: Vec<Option<GamepadState>>,
+    }
+
+    impl GamepadSource for MockGamepad {
+        fn poll(&mut self) -> Option<GamepadState> {
+            if self.states.is_empty() {
+                None
+            } else {
+                self.states.remove(0)
+            }
+        }
+    }
+
+    fn buttons(pressed: &[usize]) -> GamepadState {
+        let mut buttons = vec![false; 17];
+        for button in pressed {
+            buttons[*button] = true;
+        }
+        GamepadState {
+            axes: vec![0f64; 4],
+            buttons,
+        }
+    }
+
+    fn stick(x: f64, y: f64) -> GamepadState {
+        GamepadState {
+            axes: vec![x, y, 0f64, 0f64],
+            buttons: vec![false; 17],
+        }
+    }
+
+    #[test]
+    fn direction_from_d_pad_and_stick() {
+        assert_eq!(buttons(&[12]).direction(), Some(Direction::Up));
+        assert_eq!(buttons(&[15]).direction(), Some(Direction::Right));
+        assert_eq!(stick(-0.9, 0.2).direction(), Some(Direction::Left));
+        assert_eq!(stick(0.1, 0.7).direction(), Some(Direction::Down));
+        assert_eq!(stick(0.3, -0.2).direction(), None);
+        assert_eq!(GamepadState::default().direction(), None);
+    }
+
+    #[test]
+    fn inputs_fire_once_per_press() {
+        let mut source = MockGamepad {
+            states: vec![
+                Some(buttons(&[13])),
+                Some(buttons(&[13])),
+                Some(stick(0.8, 0f64)),
+                Some(buttons(&[9])),
+                Some(buttons(&[9])),
+                None,
+                Some(buttons(&[9, 14])),
+            ],
+        };
+        let mut mapper = GamepadMapper::default();
+        let inputs: Vec<Vec<GameInput>> = (0..7).map(|_poll| mapper.poll(&mut source)).collect();
+        assert_eq!(
+            inputs,
+            vec![
+                vec![GameInput::Move(Direction::Down)],
+                vec![],
+                vec![GameInput::Move(Direction::Right)],
+                vec![GameInput::Start],
+                vec![],
+                vec![],
+                vec![GameInput::Move(Direction::Left), GameInput::Start],
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_to_directions() {
+        assert_eq!(key_direction("ArrowUp", "ArrowUp"), Some(Direction::Up));
+        assert_eq!(key_direction("q", "KeyQ"), Some(Direction::UpLeft));
+        assert_eq!(key_direction("C", "KeyC"), Some(Direction::DownRight));
+        assert_eq!(key_direction("s", "KeyS"), Some(Direction::Down));
+        // Num lock off turns the numpad into navigation keys.
+        assert_eq!(key_direction("PageUp", "Numpad9"), Some(Direction::UpRight));
+        assert_eq!(key_direction("1", "Numpad1"), Some(Direction::DownLeft));
+        assert_eq!(key_direction("5", "Numpad5"), None);
+        assert_eq!(key_direction("p", "KeyP"), None);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::model::{Game, GameConfig, Location, Status};
+use anyhow::Result;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/**
+ * Settings JavaScript passes to `new GameHandle(config)`, every field is optional.
+ */
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HandleConfig {
+    pub rows: i32,
+    pub columns: i32,
+    pub creepers: i16,
+    // Boards are random when missing.
+    pub seed: Option<u64>,
+    pub creeper_strategy: String,
+    pub autopilot: String,
+    pub connectivity: String,
+    pub wrap: bool,
+    pub maze: String,
+}
+
+impl Default for HandleConfig {
+    fn default() -> Self {
+        HandleConfig {
+            rows: 12,
+            columns: 24,
+            creepers: 10,
+            seed: None,
+            creeper_strategy: "chase".to_string(),
+            autopilot: "greedy".to_string(),
+            connectivity: "eight".to_string(),
+            wrap: false,
+            maze: "open".to_string(),
+        }
+    }
+}
+
+impl HandleConfig {
+    fn game_config(&self) -> Result<GameConfig> {
+        let config = GameConfig {
+            creepers: self.creepers,
+            rows: self.rows,
+            columns: self.columns,
+            creeper_strategy: self.creeper_strategy.parse()?,
+            autopilot: self.autopilot.parse()?,
+            connectivity: self.connectivity.parse()?,
+            wrap: self.wrap,
+            maze: self.maze.parse()?,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/**
+ * What `state_json` returns, cells are `[row, column]` pairs.
+ */
+#[derive(Serialize)]
+struct Snapshot {
+    status: String,
+    tick: i16,
+    rows: i32,
+    columns: i32,
+    target: [i32; 2],
+    ferris: Option<[i32; 2]>,
+    creepers: Vec<[i32; 2]>,
+    path: Vec<[i32; 2]>,
+    walls: Vec<[i32; 2]>,
+    score: u32,
+}
+
+fn cell(location: &Location) -> [i32; 2] {
+    [location.row().0, location.col().0]
+}
+
+/**
+ * The game engine for web front ends that do not use the bundled Yew app. The
+ * handle owns the game, the embedder drives it with `tick` on its own timer and
+ * `move_ferris` on key presses and draws it from `state_json`.
+ */
+#[wasm_bindgen]
+pub struct GameHandle {
+    game: Game,
+    tick: i16,
+}
+
+impl GameHandle {
+    pub fn from_config(config: &HandleConfig) -> Result<GameHandle> {
+        let mut randy = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Ok(GameHandle {
+            game: Game::new(&config.game_config()?, &mut randy),
+            tick: 0,
+        })
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    fn wall_cells(&self) -> Vec<[i32; 2]> {
+        let mut walls: Vec<[i32; 2]> = self.game.walls.iter().map(cell).collect();
+        walls.sort();
+        walls
+    }
+
+    fn path_cells(&self) -> Vec<[i32; 2]> {
+        self.game
+            .moves
+            .last()
+            .map(|state| state.ferris.path.iter().map(cell).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[wasm_bindgen]
+impl GameHandle {
+    /**
+     * `config` is an object like `{ rows: 12, columns: 24, creepers: 10, seed: 7,
+     * creeperStrategy: "pack", autopilot: "lookahead", connectivity: "four", wrap: true,
+     * maze: "caves" }`, missing fields use defaults.
+     */
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: JsValue) -> Result<GameHandle, JsValue> {
+        let config = if config.is_undefined() || config.is_null() {
+            HandleConfig::default()
+        } else {
+            let json = js_sys::JSON::stringify(&config)?
+                .as_string()
+                .unwrap_or_default();
+            serde_json::from_str(&json).map_err(|err| JsValue::from_str(&err.to_string()))?
+        };
+        GameHandle::from_config(&config).map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /**
+     * Moves Ferris one cell towards `direction`, see `Direction` for the names it
+     * takes. Returns the status.
+     */
+    pub fn move_ferris(&mut self, direction: &str) -> Result<String, JsValue> {
+        let direction = direction
+            .parse()
+            .map_err(|err: anyhow::Error| JsValue::from_str(&err.to_string()))?;
+        self.game = self.game.move_ferris(direction);
+        Ok(self.status())
+    }
+
+    /**
+     * Advances the game one tick, returns the status afterwards.
+     */
+    pub fn tick(&mut self) -> String {
+        self.tick = self.tick.wrapping_add(1);
+        self.game = self.game.tick(self.tick);
+        self.status()
+    }
+
+    /**
+     * Lets the autopilot drive Ferris without waiting for the first key press.
+     */
+    pub fn start(&mut self) {
+        if self.game.status == Status::Idle {
+            self.game.status = Status::Playing;
+        }
+    }
+
+    pub fn status(&self) -> String {
+        self.game.status.to_string().to_lowercase()
+    }
+
+    /**
+     * The whole board as JSON, see `Snapshot`.
+     */
+    pub fn state_json(&self) -> String {
+        let state = self.game.moves.last();
+        let snapshot = Snapshot {
+            status: self.status(),
+            tick: self.tick,
+            rows: self.game.rows,
+            columns: self.game.columns,
+            target: cell(&self.game.target),
+            ferris: state.map(|state| cell(&state.ferris.location)),
+            creepers: state
+                .map(|state| {
+                    state
+                        .creepers
+                        .iter()
+                        .map(|creeper| cell(&creeper.location))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            path: self.path_cells(),
+            walls: self.wall_cells(),
+            score: self.game.score(),
+        };
+        serde_json::to_string(&snapshot).unwrap_or_default()
+    }
+
+    /**
+     * Ferris's path home flattened to `[row, column, row, column, ...]`.
+     */
+    pub fn path(&self) -> Vec<i32> {
+        self.path_cells().concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hunt::CreeperStrategy, model::Status};
+
+    use super::{GameHandle, HandleConfig};
+
+    fn handle_with(json: &str) -> GameHandle {
+        let config: HandleConfig = serde_json::from_str(json).unwrap();
+        GameHandle::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn config_from_json() {
+        let config: HandleConfig =
+            serde_json::from_str(r#"{"rows": 8, "creepers": 2, "creeperStrategy": "pack"}"#)
+                .unwrap();
+        assert_eq!(config.rows, 8);
+        assert_eq!(config.columns, HandleConfig::default().columns);
+        let handle = GameHandle::from_config(&config).unwrap();
+        assert_eq!(handle.game().creeper_strategy, CreeperStrategy::Pack);
+
+        let config: HandleConfig = serde_json::from_str(r#"{"autopilot": "sideways"}"#).unwrap();
+        assert!(GameHandle::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn state_json_matches_game() {
+        let handle = handle_with(r#"{"rows": 8, "columns": 6, "creepers": 3, "seed": 7}"#);
+        let again = handle_with(r#"{"rows": 8, "columns": 6, "creepers": 3, "seed": 7}"#);
+        assert_eq!(handle.state_json(), again.state_json());
+
+        let json: serde_json::Value = serde_json::from_str(&handle.state_json()).unwrap();
+        let state = handle.game().moves.last().unwrap();
+        assert_eq!(json["status"], "idle");
+        assert_eq!(json["score"], 0);
+        assert_eq!(json["creepers"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            json["ferris"],
+            serde_json::json!([state.ferris.location.row().0, state.ferris.location.col().0])
+        );
+        let path = handle.path();
+        assert_eq!(path.len(), state.ferris.path.len() * 2);
+        assert_eq!(
+            path[path.len() - 2..],
+            [handle.game().target.row().0, handle.game().target.col().0]
+        );
+    }
+
+    #[test]
+    fn move_and_tick() {
+        let mut handle = handle_with(r#"{"rows": 8, "columns": 6, "creepers": 1, "seed": 3}"#);
+        assert_eq!(handle.move_ferris("down").unwrap(), handle.status());
+        assert_ne!(handle.game().status, Status::Idle);
+        for _tick in 0..200 {
+            if handle.tick() != "playing" {
+                break;
+            }
+        }
+        assert!(matches!(handle.game().status, Status::Won | Status::Lost));
+    }
+}
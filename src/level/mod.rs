@@ -0,0 +1,438 @@
+use crate::autopilot::Autopilot;
+use crate::board::{Board, Col, Row};
+use crate::hunt::CreeperStrategy;
+use crate::maze::Maze;
+use crate::model::{Game, GameConfig, Layout, Location};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Characters of a level map.
+pub const LAWN: char = '.';
+pub const WALL: char = '#';
+pub const FERRIS: char = 'F';
+pub const HOME: char = 'H';
+pub const CREEPER: char = 'C';
+// Creepers that chase Ferris or run with the pack whatever the strategy of the level.
+pub const CHASER: char = 'X';
+pub const PACKER: char = 'P';
+
+// Levels bundled with the game, played in order in campaign mode.
+const CAMPAIGN: &str = include_str!("../../levels/campaign.json");
+
+/**
+ * A board laid out by hand.
+ *
+ * `map` has one string per line of the board as drawn on screen, the first string is
+ * the top line and every character is a cell, see `LAWN`, `WALL`, `FERRIS`, `HOME`,
+ * `CREEPER`, `CHASER` and `PACKER`. The other settings use the same names as the game
+ * settings, `creeper_strategy` is the one of `CREEPER` cells.
+ */
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Level {
+    pub name: String,
+    pub map: Vec<String>,
+    pub creeper_strategy: String,
+    pub connectivity: String,
+    pub wrap: bool,
+    // Moves a good run takes to bring Ferris home.
+    pub par: usize,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Level {
+            name: String::new(),
+            map: vec![],
+            creeper_strategy: "chase".to_string(),
+            connectivity: "eight".to_string(),
+            wrap: false,
+            par: 0,
+        }
+    }
+}
+
+/**
+ * Levels meant to be played one after the other.
+ */
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct LevelPack {
+    pub name: String,
+    pub levels: Vec<Level>,
+}
+
+impl LevelPack {
+    pub fn from_json(json: &str) -> Result<LevelPack> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/**
+ * The level pack that ships with the game.
+ */
+pub fn campaign() -> LevelPack {
+    // The pack is checked by the tests, it always parses.
+    LevelPack::from_json(CAMPAIGN).unwrap_or_default()
+}
+
+impl Level {
+    /**
+     * Open board with Ferris in the top left corner and home in the bottom right one.
+     */
+    pub fn blank(rows: usize, columns: usize) -> Level {
+        let mut level = Level::default();
+        level.resize(rows, columns);
+        level.paint(&Location::at(Row(0), Col(0)), FERRIS);
+        let (rows, columns) = level.size();
+        let home = Location::at(Row(rows as i32 - 1), Col(columns as i32 - 1));
+        level.paint(&home, HOME);
+        level
+    }
+
+    pub fn from_json(json: &str) -> Result<Level> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /**
+     * The map alone, one line of text per line of the board.
+     */
+    pub fn to_text(&self) -> String {
+        self.map.join("\n")
+    }
+
+    /**
+     * Copy of the level with the map read from `text`, blank lines and the spaces around
+     * lines are skipped. The other settings are kept.
+     */
+    pub fn with_text(&self, text: &str) -> Level {
+        let map = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        Level {
+            map,
+            ..self.clone()
+        }
+    }
+
+    /**
+     * Number of rows and columns of the map, as in `GameConfig`. Every line of the map
+     * is a row.
+     */
+    pub fn size(&self) -> (usize, usize) {
+        let columns = self.map.first().map_or(0, |line| line.chars().count());
+        (self.map.len(), columns)
+    }
+
+    /**
+     * Board the map is laid on, unknown settings fall back to the defaults.
+     */
+    pub fn board(&self) -> Board {
+        let (rows, columns) = self.size();
+        Board {
+            wrap: self.wrap,
+            ..Board::new(
+                rows as i32,
+                columns as i32,
+                self.connectivity.parse().unwrap_or_default(),
+            )
+        }
+    }
+
+    /**
+     * What the map has at `location`, lawn outside of the map.
+     */
+    pub fn cell(&self, location: &Location) -> char {
+        usize::try_from(location.row().0)
+            .ok()
+            .and_then(|row| self.map.get(row))
+            .zip(usize::try_from(location.col().0).ok())
+            .and_then(|(line, col)| line.chars().nth(col))
+            .unwrap_or(LAWN)
+    }
+
+    /**
+     * Puts `cell` at `location`, a map has a single Ferris and a single home so
+     * placing one of them moves it.
+     */
+    pub fn paint(&mut self, location: &Location, cell: char) {
+        let (rows, columns) = self.size();
+        let (row, col) = match (
+            usize::try_from(location.row().0),
+            usize::try_from(location.col().0),
+        ) {
+            (Ok(row), Ok(col)) if row < rows && col < columns => (row, col),
+            _ => return,
+        };
+        if cell == FERRIS || cell == HOME {
+            for line in self.map.iter_mut() {
+                *line = line.replace(cell, &LAWN.to_string());
+            }
+        }
+        let mut line: Vec<char> = self.map[row].chars().collect();
+        line[col] = cell;
+        self.map[row] = line.into_iter().collect();
+    }
+
+    /**
+     * Grows or shrinks the map keeping its top left corner, new cells are lawn.
+     */
+    pub fn resize(&mut self, rows: usize, columns: usize) {
+        self.map.resize(rows, String::new());
+        for line in self.map.iter_mut() {
+            let mut cells: Vec<char> = line.chars().collect();
+            cells.resize(columns, LAWN);
+            *line = cells.into_iter().collect();
+        }
+    }
+
+    /**
+     * Settings and starting positions described by the map.
+     */
+    pub fn layout(&self, autopilot: &Autopilot) -> Result<(GameConfig, Layout)> {
+        let (rows, columns) = self.size();
+        let (rows, columns) = (rows as i32, columns as i32);
+        if rows < 2 || columns < 2 {
+            return Err(anyhow!("the map needs at least 2 rows and 2 columns"));
+        }
+        let mut walls = HashSet::new();
+        let mut ferris = None;
+        let mut target = None;
+        let mut creepers = vec![];
+        let mut creeper_strategies = vec![];
+        for (row, line) in self.map.iter().enumerate() {
+            if line.chars().count() as i32 != columns {
+                return Err(anyhow!(
+                    "line {} of the map is not {} cells long",
+                    row,
+                    columns
+                ));
+            }
+            for (col, cell) in line.chars().enumerate() {
+                let location = Location::at(Row(row as i32), Col(col as i32));
+                match cell {
+                    LAWN => {}
+                    WALL => {
+                        walls.insert(location);
+                    }
+                    FERRIS if ferris.is_none() => ferris = Some(location),
+                    HOME if target.is_none() => target = Some(location),
+                    CREEPER | CHASER | PACKER => {
+                        creepers.push(location);
+                        creeper_strategies.push(match cell {
+                            CHASER => Some(CreeperStrategy::Chase),
+                            PACKER => Some(CreeperStrategy::Pack),
+                            _ => None,
+                        });
+                    }
+                    FERRIS | HOME => return Err(anyhow!("the map has more than one {:?}", cell)),
+                    _ => return Err(anyhow!("unknown cell {:?} on line {}", cell, row)),
+                }
+            }
+        }
+        let config = GameConfig {
+            creepers: creepers.len() as i16,
+            rows,
+            columns,
+            creeper_strategy: self.creeper_strategy.parse()?,
+            autopilot: autopilot.clone(),
+            connectivity: self.connectivity.parse()?,
+            wrap: self.wrap,
+            maze: Maze::Open,
+        };
+        let layout = Layout {
+            walls,
+            ferris: ferris.ok_or_else(|| anyhow!("the map has no Ferris"))?,
+            target: target.ok_or_else(|| anyhow!("the map has no home"))?,
+            creepers,
+            creeper_strategies,
+        };
+        Ok((config, layout))
+    }
+
+    /**
+     * New game of the level, Ferris is driven by `autopilot` when the player lets go.
+     */
+    pub fn game(&self, autopilot: &Autopilot) -> Result<Game> {
+        let (config, layout) = self.layout(autopilot)?;
+        Ok(Game::from_layout(&config, layout))
+    }
+
+    /**
+     * Makes sure the level can be played, Ok holds the number of moves of the shortest
+     * way home when creepers are left out.
+     */
+    pub fn check(&self) -> Result<usize> {
+        let game = self.game(&Autopilot::Greedy)?;
+        let ferris = &game.moves[0].ferris.location;
+        game.steps_to(&game.target)
+            .get(ferris)
+            .map(|steps| *steps as usize)
+            .ok_or_else(|| anyhow!("walls keep Ferris from getting home"))
+    }
+}
+
+/**
+ * How far the player got in a level pack.
+ */
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Progress {
+    // Levels before this one can be played, the first level is always unlocked.
+    pub unlocked: usize,
+    // Fewest moves Ferris took to get home on every level won so far.
+    pub best: Vec<Option<usize>>,
+}
+
+impl Progress {
+    /**
+     * Progress saved with `to_json`, a fresh start when the json can not be read.
+     */
+    pub fn from_json(json: &str) -> Progress {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn is_unlocked(&self, level: usize) -> bool {
+        level < self.unlocked.max(1)
+    }
+
+    pub fn best(&self, level: usize) -> Option<usize> {
+        self.best.get(level).copied().flatten()
+    }
+
+    /**
+     * Records a win of `level` in `moves` moves, which unlocks the next level.
+     */
+    pub fn won(&mut self, level: usize, moves: usize) {
+        self.unlocked = self.unlocked.max(level + 2);
+        if self.best.len() <= level {
+            self.best.resize(level + 1, None);
+        }
+        let best = &mut self.best[level];
+        *best = Some(best.map_or(moves, |best| best.min(moves)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        autopilot::Autopilot,
+        board::{Col, Row},
+        hunt::CreeperStrategy,
+        model::{Location, Status},
+    };
+
+    use super::{campaign, Level, Progress, CHASER, CREEPER, HOME, LAWN, PACKER, WALL};
+
+    fn level(map: &[&str]) -> Level {
+        Level {
+            map: map.iter().map(|line| line.to_string()).collect(),
+            ..Level::default()
+        }
+    }
+
+    #[test]
+    fn campaign_levels_are_playable() {
+        let pack = campaign();
+        assert!(!pack.levels.is_empty());
+        for level in &pack.levels {
+            // Par can not beat the shortest way home.
+            let shortest = level.check().unwrap();
+            assert!(level.par >= shortest, "{}", level.name);
+            let game = level.game(&Autopilot::Greedy).unwrap();
+            assert_eq!(game.status, Status::Idle, "{}", level.name);
+        }
+    }
+
+    #[test]
+    fn maps_lay_out_the_board() {
+        let (config, layout) = level(&["F.#", ".CH"]).layout(&Autopilot::Greedy).unwrap();
+        assert_eq!((config.rows, config.columns, config.creepers), (2, 3, 1));
+        assert_eq!(layout.ferris, Location::at(Row(0), Col(0)));
+        assert_eq!(layout.target, Location::at(Row(1), Col(2)));
+        assert!(layout.walls.contains(&Location::at(Row(0), Col(2))));
+        assert_eq!(layout.creepers[0], Location::at(Row(1), Col(1)));
+        assert_eq!(layout.creeper_strategies, vec![None]);
+
+        assert!(level(&["F..", "..H", "."]).check().is_err());
+        assert!(level(&["F.F", "..H"]).check().is_err());
+        assert!(level(&["F..", "..?", "..H"]).check().is_err());
+        assert!(level(&["F#.", "##H"]).check().is_err());
+        assert_eq!(level(&["F#.", ".#H", "..."]).check().unwrap(), 3);
+    }
+
+    #[test]
+    fn creepers_of_any_kind() {
+        let mut level = level(&["F.C", "X.P", "..H"]);
+        level.creeper_strategy = "pack".to_string();
+        let (_config, layout) = level.layout(&Autopilot::Greedy).unwrap();
+        assert_eq!(
+            layout.creeper_strategies,
+            vec![
+                None,
+                Some(CreeperStrategy::Chase),
+                Some(CreeperStrategy::Pack)
+            ]
+        );
+        let game = level.game(&Autopilot::Greedy).unwrap();
+        assert_eq!(game.strategy_of(0), &CreeperStrategy::Pack);
+        assert_eq!(game.strategy_of(1), &CreeperStrategy::Chase);
+        assert_eq!(game.strategy_of(2), &CreeperStrategy::Pack);
+
+        level.paint(&Location::at(Row(1), Col(1)), CHASER);
+        level.paint(&Location::at(Row(0), Col(1)), PACKER);
+        assert_eq!(level.to_text(), "FPC\nXXP\n..H");
+        assert_eq!(level.check().unwrap(), 2);
+    }
+
+    #[test]
+    fn editing_a_level() {
+        let mut level = Level::blank(3, 4);
+        assert_eq!(level.size(), (3, 4));
+        assert_eq!(level.to_text(), "F...\n....\n...H");
+        level.paint(&Location::at(Row(0), Col(1)), WALL);
+        level.paint(&Location::at(Row(1), Col(2)), CREEPER);
+        level.paint(&Location::at(Row(0), Col(3)), HOME);
+        level.paint(&Location::at(Row(9), Col(9)), WALL);
+        assert_eq!(level.to_text(), "F#.H\n..C.\n....");
+        assert_eq!(level.cell(&Location::at(Row(1), Col(2))), CREEPER);
+        assert_eq!(level.cell(&Location::at(Row(1), Col(-1))), LAWN);
+        assert_eq!(level.check().unwrap(), 3);
+
+        level.resize(4, 2);
+        assert_eq!(level.to_text(), "F#\n..\n..\n..");
+        assert_eq!(level.board().len(), 8);
+        assert!(level.check().is_err());
+
+        let level = level.with_text("  F.#\n\n  ..H \n");
+        assert_eq!(level.map, vec!["F.#", "..H"]);
+        assert_eq!(Level::from_json(&level.to_json()).unwrap(), level);
+    }
+
+    #[test]
+    fn winning_unlocks_the_next_level() {
+        let mut progress = Progress::default();
+        assert!(progress.is_unlocked(0));
+        assert!(!progress.is_unlocked(1));
+        progress.won(0, 12);
+        progress.won(0, 15);
+        assert!(progress.is_unlocked(1));
+        assert!(!progress.is_unlocked(2));
+        assert_eq!(progress.best(0), Some(12));
+        assert_eq!(progress.best(1), None);
+        assert_eq!(Progress::from_json(&progress.to_json()), progress);
+        assert_eq!(Progress::from_json("not json"), Progress::default());
+    }
+}
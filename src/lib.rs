@@ -1,2 +1,19 @@
+pub mod autopilot;
+pub mod board;
+pub mod daily;
 pub mod dijkstra;
+pub mod env;
+pub mod hunt;
+pub mod input;
+pub mod js;
+pub mod level;
+pub mod lookahead;
+pub mod maze;
+pub mod mcts;
 pub mod model;
+#[cfg(feature = "python")]
+mod python;
+pub mod render;
+pub mod share;
+pub mod simulation;
+pub mod stats;
@@ -0,0 +1,384 @@
+use crate::autopilot::now_ms;
+use crate::dijkstra::Mode;
+use crate::hunt::{steps_between, TICKS_PER_CREEPER_MOVE};
+use crate::model::{Game, GameState, Location};
+use std::collections::HashMap;
+
+/**
+ * Settings of the lookahead autopilot.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct LookaheadConfig {
+    // How many Ferris moves to look ahead.
+    pub depth: u32,
+    // Stop searching deeper once this many milliseconds went by, the best move of the
+    // deepest complete search is used.
+    pub time_budget_ms: u32,
+}
+
+impl Default for LookaheadConfig {
+    fn default() -> Self {
+        LookaheadConfig {
+            depth: 6,
+            time_budget_ms: 100,
+        }
+    }
+}
+
+const WIN_SCORE: i32 = 1_000_000;
+const HOME_WEIGHT: i32 = 100;
+// Penalty for ending the search with a creeper this many moves away from Ferris.
+const DANGER_PENALTY: [i32; 3] = [WIN_SCORE / 2, 600, 200];
+// How many nodes to visit between checks of the clock.
+const NODES_PER_CLOCK_CHECK: u32 = 64;
+
+#[derive(Clone)]
+struct Node {
+    ferris: Location,
+    creepers: Vec<Location>,
+    tick: i32,
+}
+
+struct Search<'a> {
+    game: &'a Game,
+    target: Location,
+    home_distance: HashMap<Location, i32>,
+    deadline: f64,
+    // Nodes visited since the clock was last checked.
+    nodes: u32,
+    timed_out: bool,
+}
+
+/**
+ * Picks Ferris's next move with a depth limited minimax search over Ferris and creeper
+ * moves, `tick` is the tick Ferris is about to move on.
+ *
+ * Ferris maximizes, creepers minimize: the creeper closest to Ferris tries every move
+ * while the others step straight towards Ferris, which keeps the search small enough
+ * to run within a tick. The search deepens one Ferris move at a time until it reaches
+ * the configured depth or runs out of time, a one move search always completes.
+ */
+pub fn best_move(
+    game: &Game,
+    state: &GameState,
+    tick: i16,
+    config: &LookaheadConfig,
+) -> Option<Location> {
+    let start = now_ms();
+    let mut search = Search {
+        game,
+        target: game.target.clone(),
+        home_distance: game.steps_to(&game.target),
+        deadline: f64::INFINITY,
+        nodes: 0,
+        timed_out: false,
+    };
+    let root = Node {
+        ferris: state.ferris.location.clone(),
+        creepers: state
+            .creepers
+            .iter()
+            .map(|creeper| creeper.location.clone())
+            .collect(),
+        tick: tick as i32,
+    };
+    let mut best = None;
+    for depth in 1..=config.depth.max(1) {
+        let result = search.root(&root, depth);
+        if search.timed_out {
+            break;
+        }
+        best = result;
+        search.deadline = start + config.time_budget_ms as f64;
+    }
+    best
+}
+
+impl<'a> Search<'a> {
+    fn steps(&self, from: &Location, to: &Location) -> i32 {
+        steps_between(from, to, &self.game.board())
+    }
+
+    fn root(&mut self, node: &Node, depth: u32) -> Option<Location> {
+        let mut best: Option<(i32, Location)> = None;
+        for next in self.ferris_moves(node) {
+            let child = Node {
+                ferris: next.clone(),
+                ..node.clone()
+            };
+            let alpha = best
+                .as_ref()
+                .map(|(score, _)| *score)
+                .unwrap_or(-WIN_SCORE - 1);
+            let score = self.after_ferris_move(&child, depth - 1, 1, alpha, WIN_SCORE + 1);
+            if self.timed_out {
+                return None;
+            }
+            if best
+                .as_ref()
+                .map(|(best_score, _)| score > *best_score)
+                .unwrap_or(true)
+            {
+                best = Some((score, next));
+            }
+        }
+        best.map(|(_, next)| next)
+    }
+
+    /**
+     * Scores `node` right after Ferris moved.
+     */
+    fn after_ferris_move(
+        &mut self,
+        node: &Node,
+        depth: u32,
+        ply: i32,
+        alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        if node.ferris == self.target {
+            return WIN_SCORE - ply;
+        }
+        if node.creepers.contains(&node.ferris) {
+            return -WIN_SCORE + ply;
+        }
+        if depth == 0 || self.out_of_time() {
+            return self.evaluate(node);
+        }
+        let next = Node {
+            tick: node.tick + 1,
+            ..node.clone()
+        };
+        if next.tick % TICKS_PER_CREEPER_MOVE == 0 {
+            self.creepers_move(&next, depth, ply, alpha, beta)
+        } else {
+            self.ferris_moves_next(&next, depth, ply, alpha, beta)
+        }
+    }
+
+    /**
+     * Min node, the closest creeper tries all its moves.
+     */
+    fn creepers_move(
+        &mut self,
+        node: &Node,
+        depth: u32,
+        ply: i32,
+        alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        let closest = (0..node.creepers.len())
+            .min_by_key(|index| (self.steps(&node.creepers[*index], &node.ferris), *index));
+        let mut creepers: Vec<Location> = node
+            .creepers
+            .iter()
+            .map(|creeper| self.step_towards(creeper, &node.ferris))
+            .collect();
+        let closest = match closest {
+            Some(closest) => closest,
+            None => return self.ferris_moves_next(node, depth, ply, alpha, beta),
+        };
+        let mut best = WIN_SCORE + 1;
+        for next in self.moves_from(&node.creepers[closest]) {
+            creepers[closest] = next;
+            let child = Node {
+                creepers: creepers.clone(),
+                ..node.clone()
+            };
+            let score = self.ferris_moves_next(&child, depth, ply, alpha, beta);
+            best = best.min(score);
+            beta = beta.min(score);
+            if beta <= alpha {
+                break;
+            }
+        }
+        best
+    }
+
+    /**
+     * Max node, Ferris tries all its moves.
+     */
+    fn ferris_moves_next(
+        &mut self,
+        node: &Node,
+        depth: u32,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        let mut best = -WIN_SCORE - 1;
+        for next in self.ferris_moves(node) {
+            let child = Node {
+                ferris: next,
+                ..node.clone()
+            };
+            let score = self.after_ferris_move(&child, depth - 1, ply + 1, alpha, beta);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if beta <= alpha {
+                break;
+            }
+        }
+        best
+    }
+
+    /**
+     * Ferris's moves, the ones closer to home first so that pruning kicks in early.
+     */
+    fn ferris_moves(&self, node: &Node) -> Vec<Location> {
+        let mut moves = self.moves_from(&node.ferris);
+        moves.sort_by_key(|location| self.home_distance(location));
+        moves
+    }
+
+    /**
+     * All the cells reachable in one move from `location`, including staying put.
+     */
+    fn moves_from(&self, location: &Location) -> Vec<Location> {
+        let mut moves =
+            self.game
+                .get_adjacent_vertices(location, &self.game.target, &Mode::Creeper);
+        moves.push(location.clone());
+        moves
+    }
+
+    fn step_towards(&self, from: &Location, to: &Location) -> Location {
+        self.moves_from(from)
+            .into_iter()
+            .min_by_key(|location| self.steps(location, to))
+            .unwrap_or_else(|| from.clone())
+    }
+
+    fn home_distance(&self, location: &Location) -> i32 {
+        self.home_distance
+            .get(location)
+            .copied()
+            .unwrap_or(self.game.rows * self.game.columns)
+    }
+
+    fn evaluate(&self, node: &Node) -> i32 {
+        let closest_creeper = node
+            .creepers
+            .iter()
+            .map(|creeper| self.steps(creeper, &node.ferris))
+            .min()
+            .unwrap_or(i32::MAX);
+        let danger = DANGER_PENALTY
+            .get(closest_creeper as usize)
+            .copied()
+            .unwrap_or(0);
+        -HOME_WEIGHT * self.home_distance(&node.ferris) - danger
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes >= NODES_PER_CLOCK_CHECK {
+            self.nodes = 0;
+            self.timed_out = now_ms() > self.deadline;
+        }
+        self.timed_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        autopilot::Autopilot,
+        board::{Col, Row},
+        level::Level,
+        model::{Creeper, Ferris, Game, GameState, Location, Status},
+    };
+
+    use super::{best_move, LookaheadConfig};
+
+    fn game_with(ferris: Location, creepers: Vec<Location>, target: Location) -> Game {
+        Game {
+            moves: vec![GameState {
+                creepers: creepers
+                    .into_iter()
+                    .map(|location| Creeper { location })
+                    .collect(),
+                ferris: Ferris {
+                    location: ferris,
+                    path: vec![],
+                },
+            }],
+            target,
+            status: Status::Playing,
+            autopilot: Autopilot::Lookahead(LookaheadConfig::default()),
+            ..Game::empty(8, 8)
+        }
+    }
+
+    #[test]
+    fn lookahead_goes_home() {
+        let game = game_with(
+            Location::at(Row(3), Col(3)),
+            vec![Location::at(Row(4), Col(4))],
+            Location::at(Row(2), Col(2)),
+        );
+        let config = LookaheadConfig {
+            depth: 4,
+            time_budget_ms: u32::MAX,
+        };
+        let next = best_move(&game, game.moves.last().unwrap(), 1, &config);
+        assert_eq!(next, Some(Location::at(Row(2), Col(2))));
+    }
+
+    fn play(mut game: Game) -> Status {
+        for tick in 1..60 {
+            game = game.tick(tick);
+            if game.status != Status::Playing {
+                break;
+            }
+        }
+        game.status
+    }
+
+    #[test]
+    fn lookahead_does_not_get_cornered() {
+        // Ferris starts in a nook whose only way out holds a creeper. Greedy steps into
+        // the dead end at (1, 4) and gets caught once the second creeper closes the
+        // corridor down to home, lookahead goes to (0, 3) and slips past.
+        let map = ["#.#.F", "##.C.", ".##.#", ".CH.."];
+        let config = LookaheadConfig {
+            depth: 4,
+            time_budget_ms: u32::MAX,
+        };
+        let game_for = |autopilot: Autopilot| Game {
+            status: Status::Playing,
+            ..Level {
+                map: map.iter().map(|line| line.to_string()).collect(),
+                connectivity: "four".into(),
+                ..Level::default()
+            }
+            .game(&autopilot)
+            .unwrap()
+        };
+
+        let greedy = game_for(Autopilot::Greedy);
+        let plan = Autopilot::Greedy.plan(&greedy, 1).unwrap();
+        assert_eq!(plan.path.first(), Some(&Location::at(Row(1), Col(4))));
+        assert_eq!(play(greedy), Status::Lost);
+
+        let lookahead = game_for(Autopilot::Lookahead(config.clone()));
+        let next = best_move(&lookahead, lookahead.moves.last().unwrap(), 1, &config);
+        assert_eq!(next, Some(Location::at(Row(0), Col(3))));
+        assert_eq!(play(lookahead), Status::Won);
+    }
+
+    #[test]
+    fn lookahead_without_time_still_moves() {
+        let game = game_with(
+            Location::at(Row(0), Col(0)),
+            vec![Location::at(Row(7), Col(7))],
+            Location::at(Row(5), Col(0)),
+        );
+        let config = LookaheadConfig {
+            depth: 10,
+            time_budget_ms: 0,
+        };
+        let next = best_move(&game, game.moves.last().unwrap(), 1, &config);
+        assert_eq!(next, Some(Location::at(Row(1), Col(0))));
+    }
+}
@@ -1,17 +1,195 @@
-use std::rc::Rc;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-use gloo_timers::callback::Interval;
-use survival::model::{Direction, Location, Status};
-use survival::model::{Game, GameEvents};
+use gloo_timers::callback::{Interval, Timeout};
+use survival::autopilot::{Autopilot, MoveStats};
+use survival::board::{Board, Col, Row};
+use survival::daily::{daily_config, daily_seed, date_key, DailyLog, DailyResult};
+use survival::hunt::CreeperStrategy;
+use survival::input::{key_direction, GameInput, GamepadMapper, GamepadSource, GamepadState};
+use survival::level::{campaign, Level, Progress, CHASER, CREEPER, FERRIS, HOME, LAWN, PACKER, WALL};
+use survival::lookahead::LookaheadConfig;
+use survival::maze::Maze;
+use survival::mcts::MctsConfig;
+use survival::model::{Connectivity, Direction, Location, Status};
+use survival::model::{Game, GameConfig, GameEvents, RunStats};
+use survival::render::{actors, Actor, Frame, Sprite};
+use survival::share::SharedGame;
+use survival::stats::Stats;
+use rand::Rng;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::*;
 use yew::{prelude::*, virtual_dom::VNode};
 
-const ROWS: i32 = 24;
-const COLUMNS: i32 = 12;
+const ROWS: i32 = 12;
+const COLUMNS: i32 = 24;
 const CREEPERS: i16 = 10;
-const RESTART_TEXT :&str = "Restart";
+const REPLAY_TEXT: &str = "Replay same seed";
+const NEW_SEED_TEXT: &str = "New seed";
+// Milliseconds between ticks, the speed slider goes from MIN_TICK_MS to MAX_TICK_MS.
+const DEFAULT_TICK_MS: u32 = 500;
+const MIN_TICK_MS: u32 = 100;
+const MAX_TICK_MS: u32 = 2000;
+// Milliseconds between polls of the gamepad.
+const GAMEPAD_POLL_MS: u32 = 50;
+// Size in pixels of a cell of the canvas renderer, cells of big boards shrink so the
+// canvas is at most MAX_CANVAS_SIZE pixels wide or high.
+const CELL_SIZE: f64 = 40f64;
+const MAX_CANVAS_SIZE: f64 = 1600f64;
+// localStorage key of the campaign progress.
+const PROGRESS_KEY: &str = "survival.campaign";
+// localStorage key of the daily challenge results.
+const DAILY_KEY: &str = "survival.daily";
+// localStorage key of the player stats.
+const STATS_KEY: &str = "survival.stats";
+// Seeds on the leaderboard of the stats screen.
+const LEADERBOARD_SIZE: usize = 10;
+// Milliseconds the win screen stays up before the next campaign level starts.
+const NEXT_LEVEL_MS: u32 = 1500;
+// Smallest and largest boards of the level editor.
+const MIN_EDITOR_SIZE: usize = 2;
+const MAX_EDITOR_SIZE: usize = 40;
+// What the level editor paints, with the label of its button.
+const EDITOR_TOOLS: [(char, &str); 7] = [
+    (LAWN, "Lawn"),
+    (WALL, "Wall"),
+    (FERRIS, "Ferris"),
+    (HOME, "Home"),
+    (CREEPER, "Creeper"),
+    (CHASER, "Chaser"),
+    (PACKER, "Packer"),
+];
+
+/**
+ * How the board is drawn, Cells renders a Yew component per cell, Canvas draws the
+ * whole board on a canvas which keeps big boards smooth.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Renderer {
+    Cells,
+    Canvas,
+}
+
+fn new_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+fn local_storage() -> Option<Storage> {
+    window()?.local_storage().ok().flatten()
+}
+
+// Campaign progress of this browser, a fresh start the first time.
+fn load_progress() -> Progress {
+    local_storage()
+        .and_then(|storage| storage.get_item(PROGRESS_KEY).ok().flatten())
+        .map(|json| Progress::from_json(&json))
+        .unwrap_or_default()
+}
+
+fn save_progress(progress: &Progress) {
+    if let Some(storage) = local_storage() {
+        let _result = storage.set_item(PROGRESS_KEY, &progress.to_json());
+    }
+}
+
+// Daily results of this browser.
+fn load_daily_log() -> DailyLog {
+    local_storage()
+        .and_then(|storage| storage.get_item(DAILY_KEY).ok().flatten())
+        .map(|json| DailyLog::from_json(&json))
+        .unwrap_or_default()
+}
+
+fn save_daily_log(log: &DailyLog) {
+    if let Some(storage) = local_storage() {
+        let _result = storage.set_item(DAILY_KEY, &log.to_json());
+    }
+}
+
+// Stats of this browser, stats that can not be read start over.
+fn load_stats() -> Stats {
+    local_storage()
+        .and_then(|storage| storage.get_item(STATS_KEY).ok().flatten())
+        .and_then(|json| Stats::from_json(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_stats(stats: &Stats) {
+    if let Some(storage) = local_storage() {
+        let _result = storage.set_item(STATS_KEY, &stats.to_json());
+    }
+}
+
+// Year, month and day of today where the player is.
+fn today() -> (u32, u32, u32) {
+    let date = js_sys::Date::new_0();
+    (date.get_full_year(), date.get_month() + 1, date.get_date())
+}
+
+// Copies the text of `area` to the clipboard.
+fn copy_text(area: &NodeRef) {
+    if let Some(area) = area.cast::<HtmlTextAreaElement>() {
+        area.select();
+        let document = window().and_then(|window| window.document());
+        if let Some(document) = document.and_then(|document| document.dyn_into::<HtmlDocument>().ok()) {
+            let _result = document.exec_command("copy");
+        }
+    }
+}
+
+// Settings of the first game when the page link does not pick any.
+fn default_config() -> GameConfig {
+    GameConfig {
+        creepers: CREEPERS,
+        rows: ROWS,
+        columns: COLUMNS,
+        creeper_strategy: CreeperStrategy::default(),
+        autopilot: Autopilot::default(),
+        connectivity: Connectivity::default(),
+        wrap: false,
+        maze: Maze::Open,
+    }
+}
+
+// Settings for the next game, the board size, creepers, autopilot and the board layout are
+// carried over from `game`.
+fn game_config(game: &Game) -> GameConfig {
+    let creepers = match game.moves.first() {
+        Some(first) => first.creepers.len() as i16,
+        // No game has started yet.
+        None => return default_config(),
+    };
+    GameConfig {
+        creepers,
+        rows: game.rows,
+        columns: game.columns,
+        creeper_strategy: game.creeper_strategy.clone(),
+        autopilot: game.autopilot.clone(),
+        connectivity: game.connectivity,
+        wrap: game.wrap,
+        maze: game.maze,
+    }
+}
+
+// Game picked by the link the page was opened with, unreadable settings fall back to `fallback`.
+fn linked_game(fallback: &SharedGame) -> SharedGame {
+    let query = window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default();
+    SharedGame::from_query(&query, fallback)
+}
+
+// Points the address bar at `shared` so it can be copied, without reloading the page.
+fn show_link(shared: &SharedGame) {
+    if let Some(history) = window().and_then(|window| window.history().ok()) {
+        let url = format!("?{}", shared.to_query());
+        let _result = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+    }
+}
 
 #[derive(Properties, Debug, PartialEq)]
 pub struct GameContextProviderProps {
@@ -20,8 +198,7 @@ pub struct GameContextProviderProps {
 }
 #[derive(PartialEq, Properties)]
 struct CellProps {
-    row: i32,
-    column: i32,
+    location: Location,
 }
 
 
@@ -31,8 +208,17 @@ pub fn GameContextProviderImpl(props: &GameContextProviderProps) -> Html {
         moves: vec![],
         rows: 0,
         columns: 0,
-        target: Location { x: 0, y: 0 },
+        target: Location::at(Row(0), Col(0)),
         status: Status::Idle,
+        creeper_strategy: CreeperStrategy::default(),
+        creeper_strategies: vec![],
+        autopilot: Autopilot::default(),
+        connectivity: Connectivity::default(),
+        wrap: false,
+        maze: Maze::Open,
+        walls: HashSet::new(),
+        move_stats: vec![],
+        stats: RunStats::default(),
     });
 
 
@@ -46,11 +232,7 @@ pub fn GameContextProviderImpl(props: &GameContextProviderProps) -> Html {
 
 #[function_component(Cell)]
 fn cell(p: &CellProps) -> Html {
-    let CellProps { row, column } = p;
-    let current_location = Location {
-        x: *row,
-        y: *column,
-    };
+    let current_location = &p.location;
     let game_state = use_context::<UseReducerHandle<Game>>().unwrap();
 
     let is_creeper = game_state
@@ -61,8 +243,7 @@ fn cell(p: &CellProps) -> Html {
                 game_move
                     .creepers
                     .iter()
-                    .find(|creeper| creeper.location.x == *row && creeper.location.y == *column)
-                    .is_some()
+                    .any(|creeper| creeper.location == *current_location)
             }
         })
         .unwrap_or(false);
@@ -70,15 +251,15 @@ fn cell(p: &CellProps) -> Html {
     let is_ferris = game_state
         .moves
         .last()
-        .map(|g| g.ferris.location.x == *row && g.ferris.location.y == *column)
+        .map(|g| g.ferris.location == *current_location)
         .unwrap_or(false);
 
-    let is_home = game_state.target.x == *row && game_state.target.y == *column;
+    let is_home = game_state.target == *current_location;
 
     let is_path = game_state
         .moves
         .last()
-        .map(|g| g.ferris.path.contains(&current_location))
+        .map(|g| g.ferris.path.contains(current_location))
         .unwrap_or(false);
 
     let ferris_image = if is_ferris {
@@ -131,109 +312,1489 @@ fn cell(p: &CellProps) -> Html {
         }
     };
 
+    // Debug overlay, how many playouts the autopilot spent on moving Ferris here.
+    let move_stats = game_state
+        .move_stats
+        .iter()
+        .find(|stats| stats.location == *current_location)
+        .map(|stats| {
+            html! {
+                <div class="move_stats">{format!("{} ({:.2})", stats.visits, stats.value)}</div>
+            }
+        })
+        .unwrap_or_else(|| html! { <></> });
+
+    let board = game_state.board();
+    let (class, style) = match board.connectivity {
+        Connectivity::Hex => (classes!("cell", "hex_cell"), hex_cell_style(current_location)),
+        Connectivity::Four | Connectivity::Eight => (classes!("cell"), String::new()),
+    };
+    let class = if game_state.walls.contains(current_location) {
+        classes!(class, "wall_cell")
+    } else {
+        class
+    };
+
     html! {
-        <div class = "cell">
+        <div class={class} style={style}>
             {creeper_image}
             {ferris_image}
             {home_image}
             {is_path_image}
+            {move_stats}
         </div>
     }
 }
 
+// Every sprite that has a thumbnail.
+const SPRITES: [Sprite; 5] = [
+    Sprite::Home,
+    Sprite::Creeper,
+    Sprite::Ferris,
+    Sprite::FerrisHome,
+    Sprite::FerrisCaught,
+];
+
+/**
+ * Loads the thumbnail of every sprite, `on_load` is called each time one finishes.
+ */
+fn load_images(on_load: Rc<dyn Fn()>) -> HashMap<&'static str, HtmlImageElement> {
+    SPRITES
+        .iter()
+        .filter_map(|sprite| sprite.image())
+        .filter_map(|src| {
+            let image = HtmlImageElement::new().ok()?;
+            let on_load = on_load.clone();
+            let callback = Closure::wrap(Box::new(move || on_load()) as Box<dyn Fn()>);
+            image.set_onload(Some(callback.as_ref().unchecked_ref()));
+            callback.forget();
+            image.set_src(src);
+            Some((src, image))
+        })
+        .collect()
+}
+
+/**
+ * Size in pixels of the cells of `board` on the canvas.
+ */
+fn cell_size(board: &Board) -> f64 {
+    let (width, height) = board.screen_size();
+    (MAX_CANVAS_SIZE / width.max(height)).min(CELL_SIZE)
+}
+
+/**
+ * Size in pixels of the canvas `board` is drawn on.
+ */
+fn canvas_size(board: &Board) -> (u32, u32) {
+    let (width, height) = board.screen_size();
+    let size = cell_size(board);
+    ((width * size).ceil() as u32, (height * size).ceil() as u32)
+}
+
+fn context_2d(canvas: &HtmlCanvasElement) -> Result<CanvasRenderingContext2d, JsValue> {
+    Ok(canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("canvas has no 2d context"))?
+        .dyn_into::<CanvasRenderingContext2d>()?)
+}
+
+// Grid lines of the last board drawn, on a canvas of their own.
+type GridCache = Rc<RefCell<Option<(Board, HtmlCanvasElement)>>>;
+
+/**
+ * Offscreen canvas with the outline of every cell of `board`, it is only drawn again
+ * when the board changes.
+ */
+fn grid(board: &Board, cache: &GridCache) -> Result<HtmlCanvasElement, JsValue> {
+    if let Some((cached, canvas)) = cache.borrow().as_ref() {
+        if cached == board {
+            return Ok(canvas.clone());
+        }
+    }
+    let canvas: HtmlCanvasElement = window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?
+        .create_element("canvas")?
+        .dyn_into()?;
+    let (width, height) = canvas_size(board);
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = context_2d(&canvas)?;
+    let size = cell_size(board);
+    context.set_stroke_style(&JsValue::from_str("rgb(19, 42, 19)"));
+    context.begin_path();
+    for location in board.locations() {
+        let mut outline = board.outline(&location).into_iter();
+        if let Some((x, y)) = outline.next() {
+            context.move_to(x * size, y * size);
+        }
+        for (x, y) in outline {
+            context.line_to(x * size, y * size);
+        }
+        context.close_path();
+    }
+    context.stroke();
+    cache.replace(Some((*board, canvas.clone())));
+    Ok(canvas)
+}
+
+fn draw_image(
+    context: &CanvasRenderingContext2d,
+    image: &HtmlImageElement,
+    (x, y): (f64, f64),
+    size: f64,
+    mirrored: bool,
+) -> Result<(), JsValue> {
+    if !mirrored {
+        return context.draw_image_with_html_image_element_and_dw_and_dh(image, x, y, size, size);
+    }
+    context.save();
+    context.translate(x + size, y)?;
+    context.scale(-1f64, 1f64)?;
+    let result =
+        context.draw_image_with_html_image_element_and_dw_and_dh(image, 0f64, 0f64, size, size);
+    context.restore();
+    result
+}
+
+/**
+ * Draws the board with Ferris and creepers `progress` (0 to 1) of the way through
+ * their last move.
+ */
+fn draw_board(
+    canvas: &HtmlCanvasElement,
+    frame: &Frame,
+    actors: &[Actor],
+    progress: f64,
+    move_stats: &[MoveStats],
+    images: &HashMap<&'static str, HtmlImageElement>,
+    grid_cache: &GridCache,
+) -> Result<(), JsValue> {
+    let context = context_2d(canvas)?;
+    let board = &frame.board;
+    let size = cell_size(board);
+    let image = |sprite: Sprite| {
+        sprite
+            .image()
+            .and_then(|src| images.get(src))
+            .filter(|image| image.complete() && image.natural_width() > 0)
+    };
+    // The lawn is the page background showing through.
+    context.clear_rect(0f64, 0f64, canvas.width() as f64, canvas.height() as f64);
+    context.draw_image_with_html_canvas_element(&grid(board, grid_cache)?, 0f64, 0f64)?;
+    let screen = |location: &Location| {
+        let (x, y) = board.screen_position(location);
+        (x * size, y * size)
+    };
+    for (location, sprite) in frame.cells().filter(|(_location, sprite)| !sprite.is_actor()) {
+        let (x, y) = screen(&location);
+        if sprite == Sprite::Path {
+            context.set_fill_style(&JsValue::from_str("rgba(0, 0, 255, 0.5)"));
+            context.fill_rect(x, y, size, size);
+        } else if sprite == Sprite::Wall {
+            context.set_fill_style(&JsValue::from_str("rgb(19, 42, 19)"));
+            context.begin_path();
+            for (x, y) in board.outline(&location) {
+                context.line_to(x * size, y * size);
+            }
+            context.close_path();
+            context.fill();
+        } else if let Some(image) = image(sprite) {
+            draw_image(&context, image, (x, y), size, false)?;
+        }
+    }
+    for actor in actors {
+        let (x, y) = actor.position(board, progress);
+        if let Some(image) = image(actor.sprite) {
+            let mirrored = actor.facing == Direction::Left;
+            draw_image(&context, image, (x * size, y * size), size, mirrored)?;
+        }
+    }
+    // Debug overlay, how many playouts the autopilot spent on moving Ferris there.
+    context.set_font("10px Helvetica");
+    for stats in move_stats {
+        let (x, y) = screen(&stats.location);
+        let y = y + size;
+        let text = format!("{} ({:.2})", stats.visits, stats.value);
+        context.set_fill_style(&JsValue::from_str("rgba(0, 0, 0, 0.6)"));
+        context.fill_rect(x, y - 12f64, size, 12f64);
+        context.set_fill_style(&JsValue::from_str("white"));
+        context.fill_text(&text, x + 1f64, y - 2f64)?;
+    }
+    Ok(())
+}
+
+// Callback of `request_animation_frame` that schedules itself again.
+type AnimationFrame = Rc<RefCell<Option<Closure<dyn FnMut()>>>>;
+
+/**
+ * Calls `draw` with the progress of the animation on every animation frame until
+ * `duration_ms` went by or `cancelled` is set.
+ */
+fn animate(
+    duration_ms: f64,
+    cancelled: Rc<std::cell::Cell<bool>>,
+    draw: impl Fn(f64) + 'static,
+) {
+    let start = js_sys::Date::now();
+    let callback: AnimationFrame = Rc::default();
+    let next_frame = callback.clone();
+    *callback.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+        if cancelled.get() {
+            // Drops the closure, it is not needed anymore.
+            next_frame.borrow_mut().take();
+            return;
+        }
+        let progress = if duration_ms > 0f64 {
+            ((js_sys::Date::now() - start) / duration_ms).min(1f64)
+        } else {
+            1f64
+        };
+        draw(progress);
+        if progress < 1f64 {
+            if let Some(closure) = next_frame.borrow().as_ref() {
+                let _result = window()
+                    .unwrap()
+                    .request_animation_frame(closure.as_ref().unchecked_ref());
+            }
+        } else {
+            next_frame.borrow_mut().take();
+        }
+    }) as Box<dyn FnMut()>));
+    if let Some(closure) = callback.borrow().as_ref() {
+        let _result = window()
+            .unwrap()
+            .request_animation_frame(closure.as_ref().unchecked_ref());
+    };
+}
+
+#[derive(PartialEq, Properties)]
+struct CanvasBoardProps {
+    // Moves are animated over a whole tick.
+    tick_ms: u32,
+}
+
+#[function_component(CanvasBoard)]
+fn canvas_board(props: &CanvasBoardProps) -> Html {
+    let tick_ms = props.tick_ms;
+    let game_state = use_context::<UseReducerHandle<Game>>().unwrap();
+    let canvas_ref = use_node_ref();
+    // Bumped every time a thumbnail finishes loading so the board is drawn again.
+    let loaded = use_state(|| 0);
+    let images = {
+        let loaded = loaded.clone();
+        use_state(move || {
+            let count = Rc::new(std::cell::Cell::new(0));
+            load_images(Rc::new(move || {
+                count.set(count.get() + 1);
+                loaded.set(count.get());
+            }))
+        })
+    };
+    // Actors of the last animation, redrawing the board does not replay their move.
+    let animated = use_mut_ref(Vec::<Actor>::new);
+    let grid_cache: GridCache = use_mut_ref(|| None);
+    let frame = Frame::from_game(&game_state);
+    let actors = actors(&game_state);
+    {
+        let canvas_ref = canvas_ref.clone();
+        let images = images.clone();
+        let grid_cache = grid_cache.clone();
+        use_effect_with_deps(
+            move |(frame, actors, move_stats, _loaded)| {
+                let cancelled = Rc::new(std::cell::Cell::new(false));
+                let (frame, actors) = (frame.clone(), actors.clone());
+                let move_stats = move_stats.clone();
+                // Moves take a whole tick, the next state shows up as they finish.
+                let replay = *animated.borrow() != actors;
+                animated.replace(actors.clone());
+                let duration = if replay { tick_ms as f64 } else { 0f64 };
+                animate(duration, cancelled.clone(), move |progress| {
+                    if let Some(canvas) = canvas_ref.cast::<HtmlCanvasElement>() {
+                        let result = draw_board(
+                            &canvas,
+                            &frame,
+                            &actors,
+                            progress,
+                            &move_stats,
+                            &images,
+                            &grid_cache,
+                        );
+                        if let Err(err) = result {
+                            gloo_console::error!(err);
+                        }
+                    }
+                });
+                move || cancelled.set(true)
+            },
+            (frame.clone(), actors, game_state.move_stats.clone(), *loaded),
+        );
+    }
+
+    let (width, height) = canvas_size(&game_state.board());
+    html! {
+        <canvas
+            class="board_canvas"
+            ref={canvas_ref}
+            width={width.to_string()}
+            height={height.to_string()}
+        />
+    }
+}
+
+/**
+ * Gamepads connected to the browser, the first one found drives Ferris.
+ */
+struct NavigatorGamepads;
+
+impl GamepadSource for NavigatorGamepads {
+    fn poll(&mut self) -> Option<GamepadState> {
+        let gamepads = window()?.navigator().get_gamepads().ok()?;
+        let gamepad = gamepads
+            .iter()
+            .find_map(|gamepad| gamepad.dyn_into::<Gamepad>().ok())?;
+        let axes = gamepad.axes().iter().filter_map(|axis| axis.as_f64()).collect();
+        let buttons = gamepad
+            .buttons()
+            .iter()
+            .map(|button| {
+                button
+                    .dyn_into::<GamepadButton>()
+                    .map(|button| button.pressed())
+                    .unwrap_or(false)
+            })
+            .collect();
+        Some(GamepadState { axes, buttons })
+    }
+}
+
 #[function_component(GameRoot)]
 fn game_root_component() -> Html {
     let game_state = Rc::new(use_context::<UseReducerHandle<Game>>().unwrap());
-    let game_state_2 = game_state.clone();
-    let game_state_3 = game_state.clone();
-    use_effect_with_deps(
-        move |_| {
-            game_state.dispatch(GameEvents::InitGameWithCreepers(CREEPERS, ROWS, COLUMNS));
-            let game_state = game_state.clone();
-            let game_state_2 = game_state.clone();
-            let mut counter = 0;
-
-            let keyboard_callback = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
-                let direction = match event.key().as_str() {
-                    "ArrowUp" => Some(Direction::Up),
-                    "ArrowLeft" => Some(Direction::Left),
-                    "ArrowRight" => Some(Direction::Right),
-                    "ArrowDown" => Some(Direction::Down),
-                    _ => None,
+    // Seed of the board being played, the end of game screen can replay it.
+    let seed = use_state(new_seed);
+    // Campaign level on screen, None in free play.
+    let level = use_state(|| None::<usize>);
+    let show_levels = use_state(|| false);
+    let pack = use_state(|| Rc::new(campaign()));
+    let progress = use_state(load_progress);
+    // Level of the editor, kept while it is test played.
+    let draft = use_state(|| Level::blank(ROWS as usize, COLUMNS as usize));
+    let editing = use_state(|| false);
+    let testing = use_state(|| false);
+    // Date of the daily challenge being played, None for other games.
+    let daily = use_state(|| None::<String>);
+    let daily_log = use_state(load_daily_log);
+    let daily_result = use_state(|| None::<DailyResult>);
+    // When the daily run started, in milliseconds.
+    let daily_started = use_mut_ref(|| None::<f64>);
+    let summary_ref = use_node_ref();
+    let stats = use_state(load_stats);
+    let show_stats = use_state(|| false);
+    // Random boards are free play, starting one leaves the campaign.
+    let start_game: Rc<dyn Fn(GameConfig, u64)> = {
+        let game_state = game_state.clone();
+        let seed = seed.clone();
+        let level = level.clone();
+        let testing = testing.clone();
+        let daily = daily.clone();
+        Rc::new(move |config, new_seed| {
+            seed.set(new_seed);
+            level.set(None);
+            testing.set(false);
+            daily.set(None);
+            show_link(&SharedGame { seed: new_seed, config: config.clone() });
+            game_state.dispatch(GameEvents::InitGameWithCreepers(config, new_seed));
+        })
+    };
+    let load_level: Rc<dyn Fn(usize)> = {
+        let game_state = game_state.clone();
+        let pack = (*pack).clone();
+        let level = level.clone();
+        let show_levels = show_levels.clone();
+        let testing = testing.clone();
+        let daily = daily.clone();
+        Rc::new(move |index| {
+            // The bundled levels are checked by the tests, they always load.
+            if let Some(Ok(game)) = pack.levels.get(index).map(|level| level.game(&game_state.autopilot)) {
+                level.set(Some(index));
+                show_levels.set(false);
+                testing.set(false);
+                daily.set(None);
+                game_state.dispatch(GameEvents::Load(Rc::new(game)));
+            }
+        })
+    };
+    let play_draft: Rc<dyn Fn(Level)> = {
+        let game_state = game_state.clone();
+        let level = level.clone();
+        let editing = editing.clone();
+        let testing = testing.clone();
+        let daily = daily.clone();
+        Rc::new(move |draft: Level| {
+            if let Ok(game) = draft.game(&game_state.autopilot) {
+                level.set(None);
+                daily.set(None);
+                editing.set(false);
+                testing.set(true);
+                game_state.dispatch(GameEvents::Load(Rc::new(game)));
+            }
+        })
+    };
+    let first_seed = *seed;
+    let start_first_game = start_game.clone();
+    let renderer = use_state(|| Renderer::Cells);
+    let tick_ms = use_state(|| DEFAULT_TICK_MS);
+    // Last tick played, shared by the timer and the step button.
+    let counter = use_mut_ref(|| 0i16);
+    // Status and settings of the game on screen, for callbacks that outlive a render.
+    let latest = use_mut_ref(|| (Status::Idle, game_config(&game_state)));
+    *latest.borrow_mut() = (game_state.status.clone(), game_config(&game_state));
+    // Campaign level or test played draft on screen, Start replays them instead of a random board.
+    let replay = use_mut_ref(|| (None::<usize>, None::<Level>));
+    *replay.borrow_mut() = (*level, testing.then(|| (*draft).clone()));
+    {
+        let game_state = game_state.clone();
+        let latest = latest.clone();
+        use_effect_with_deps(
+            move |_| {
+                // A shared link replays its board, other pages start a random one.
+                let shared = linked_game(&SharedGame { seed: first_seed, config: default_config() });
+                start_first_game(shared.config, shared.seed);
+
+                let keyboard_callback = {
+                    let game_state = game_state.clone();
+                    Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+                        if let Some(direction) = key_direction(&event.key(), &event.code()) {
+                            event.prevent_default();
+                            game_state.dispatch(GameEvents::MoveFerris(direction));
+                        } else if event.key() == "p" || event.key() == " " {
+                            event.prevent_default();
+                            game_state.dispatch(GameEvents::TogglePause);
+                        }
+                    }) as Box<dyn FnMut(_)>)
                 };
-                if let Some(direction) = direction {
-                    event.prevent_default();
-                    game_state.dispatch(GameEvents::MoveFerris(direction));
+                let _result = window().unwrap().add_event_listener_with_callback(
+                    "keydown".into(),
+                    keyboard_callback.as_ref().unchecked_ref(),
+                );
+
+                // Swipes move Ferris on touch screens.
+                let swipe_start: Rc<std::cell::Cell<Option<(f64, f64)>>> = Rc::default();
+                let touch_start_callback = {
+                    let swipe_start = swipe_start.clone();
+                    Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
+                        let touch = event.touches().get(0);
+                        swipe_start.set(
+                            touch.map(|touch| (touch.client_x() as f64, touch.client_y() as f64)),
+                        );
+                    }) as Box<dyn FnMut(_)>)
+                };
+                let touch_end_callback = Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
+                    let start = swipe_start.take();
+                    let end = event.changed_touches().get(0);
+                    if let (Some((x, y)), Some(end)) = (start, end) {
+                        let (dx, dy) = (end.client_x() as f64 - x, end.client_y() as f64 - y);
+                        let config = latest.borrow().1.clone();
+                        let board = Board::new(config.rows, config.columns, config.connectivity);
+                        // Hexes have no straight up or down, swipes go to the closest hex.
+                        let direction = Direction::from_swipe(dx, dy).and_then(|direction| {
+                            if board.connectivity.allows(direction) {
+                                Some(direction)
+                            } else {
+                                board.direction_towards(dx, dy)
+                            }
+                        });
+                        if let Some(direction) = direction {
+                            game_state.dispatch(GameEvents::MoveFerris(direction));
+                        }
+                    }
+                }) as Box<dyn FnMut(_)>);
+                let _result = window().unwrap().add_event_listener_with_callback(
+                    "touchstart",
+                    touch_start_callback.as_ref().unchecked_ref(),
+                );
+                let _result = window().unwrap().add_event_listener_with_callback(
+                    "touchend",
+                    touch_end_callback.as_ref().unchecked_ref(),
+                );
+                // The listeners go away with the component, restarting a game does not add more.
+                move || {
+                    let window = window().unwrap();
+                    let _result = window.remove_event_listener_with_callback(
+                        "keydown",
+                        keyboard_callback.as_ref().unchecked_ref(),
+                    );
+                    let _result = window.remove_event_listener_with_callback(
+                        "touchstart",
+                        touch_start_callback.as_ref().unchecked_ref(),
+                    );
+                    let _result = window.remove_event_listener_with_callback(
+                        "touchend",
+                        touch_end_callback.as_ref().unchecked_ref(),
+                    );
+                }
+            },
+            (),
+        );
+    }
+    {
+        let game_state = game_state.clone();
+        let start_game = start_game.clone();
+        let load_level = load_level.clone();
+        let play_draft = play_draft.clone();
+        use_effect_with_deps(
+            move |_| {
+                let mut source = NavigatorGamepads;
+                let mut mapper = GamepadMapper::default();
+                let interval = Interval::new(GAMEPAD_POLL_MS, move || {
+                    for input in mapper.poll(&mut source) {
+                        match input {
+                            GameInput::Move(direction) => {
+                                game_state.dispatch(GameEvents::MoveFerris(direction));
+                            }
+                            GameInput::Start => {
+                                let (status, config) = latest.borrow().clone();
+                                let replay = replay.borrow().clone();
+                                match status {
+                                    Status::Won | Status::Lost | Status::Error(_) => match replay {
+                                        (Some(index), _) => load_level(index),
+                                        (None, Some(draft)) => play_draft(draft),
+                                        (None, None) => start_game(config, new_seed()),
+                                    },
+                                    _ => game_state.dispatch(GameEvents::TogglePause),
+                                }
+                            }
+                        }
+                    }
+                });
+                move || drop(interval)
+            },
+            (),
+        );
+    }
+    // Winning a campaign level unlocks the next one, which starts after a short break.
+    {
+        let load_level = load_level.clone();
+        let progress = progress.clone();
+        let won = game_state.status == Status::Won;
+        let moves = game_state.ferris_moves();
+        let levels = pack.levels.len();
+        use_effect_with_deps(
+            move |(won, level, show_levels)| {
+                let mut timeout = None;
+                if let (true, Some(level)) = (*won, *level) {
+                    let mut next = (*progress).clone();
+                    next.won(level, moves);
+                    save_progress(&next);
+                    progress.set(next);
+                    let next_level = level + 1;
+                    if next_level < levels && !show_levels {
+                        timeout = Some(Timeout::new(NEXT_LEVEL_MS, move || load_level(next_level)));
+                    }
+                }
+                move || drop(timeout)
+            },
+            (won, *level, *show_levels),
+        );
+    }
+    // The daily challenge is timed from the first move, only the first try of a day is logged.
+    {
+        let status = game_state.status.clone();
+        let game_state = game_state.clone();
+        let daily_log = daily_log.clone();
+        let daily_result = daily_result.clone();
+        use_effect_with_deps(
+            move |(status, daily)| {
+                match (status, daily) {
+                    (_, None) | (Status::Idle, _) => {
+                        *daily_started.borrow_mut() = None;
+                        daily_result.set(None);
+                    }
+                    (Status::Playing, Some(_)) => {
+                        daily_started.borrow_mut().get_or_insert_with(js_sys::Date::now);
+                    }
+                    (Status::Won | Status::Lost, Some(date)) => {
+                        let started = daily_started.borrow_mut().take();
+                        let seconds = started.map_or(0f64, |started| (js_sys::Date::now() - started) / 1000f64);
+                        let result = DailyResult::new(&game_state, date, seconds);
+                        let mut log = (*daily_log).clone();
+                        if log.record(result.clone()) {
+                            save_daily_log(&log);
+                            daily_log.set(log);
+                        }
+                        daily_result.set(Some(result));
+                    }
+                    _ => {}
+                }
+                || ()
+            },
+            (status, (*daily).clone()),
+        );
+    }
+    // Every finished game counts towards the stats, seeded boards also count for their seed.
+    {
+        let status = game_state.status.clone();
+        let game_state = game_state.clone();
+        let stats = stats.clone();
+        let seed = (level.is_none() && !*testing).then_some(*seed);
+        use_effect_with_deps(
+            move |status| {
+                if matches!(status, Status::Won | Status::Lost) {
+                    let mut next = (*stats).clone();
+                    next.record(seed, &game_state);
+                    save_stats(&next);
+                    stats.set(next);
                 }
-            }) as Box<dyn FnMut(_)>);
-            let _result = window().unwrap().add_event_listener_with_callback(
-                "keydown".into(),
-                keyboard_callback.as_ref().unchecked_ref(),
-            );
-            keyboard_callback.forget();
-            let interval = Interval::new(500, move || {
-                counter += 1;
-                game_state_2.dispatch(GameEvents::Tick(counter));
-            });
-            move || drop(interval)
-        },
-        (),
-    );
-
-    let is_home = game_state_2.status == Status::Won;
-    let instructions = match &(*game_state_2).status {
-        Status::Idle => "Press any arrow key to start".to_string(),
-        Status::Won => "Congrats, Ferris is home! please refresh to start another game".to_string(),
-        Status::Lost => "We lost :( please refresh to start another game.".to_string(),
+                || ()
+            },
+            status,
+        );
+    }
+    // The timer starts over whenever the speed changes.
+    {
+        let game_state = game_state.clone();
+        let counter = counter.clone();
+        use_effect_with_deps(
+            move |tick_ms| {
+                let interval = Interval::new(*tick_ms, move || {
+                    let tick = counter.borrow().wrapping_add(1);
+                    *counter.borrow_mut() = tick;
+                    game_state.dispatch(GameEvents::Tick(tick));
+                });
+                move || drop(interval)
+            },
+            *tick_ms,
+        );
+    }
+
+    let is_over = matches!(game_state.status, Status::Won | Status::Lost | Status::Error(_));
+    let instructions = match &game_state.status {
+        Status::Idle => "Press any arrow key (or QWE, ASD, ZXC for diagonals) to start".to_string(),
+        Status::Won => "Congrats, Ferris is home!".to_string(),
+        Status::Lost => "We lost :(".to_string(),
         Status::Playing => "Help Ferris to get home, avoid creepers. (if you do not press the arrows, Ferris will move on it's own)".to_string(),
+        Status::Paused => "Paused, press P or space to resume.".to_string(),
         Status::Error(e) =>  format!("JEEEEZ, this is embarassing, but a bug creeped up {}", e.clone())
     };
 
-    let handle_click_restart =  move |event:web_sys::MouseEvent| { 
-        event.prevent_default();
-        game_state_3.dispatch(GameEvents::InitGameWithCreepers(CREEPERS, ROWS, COLUMNS));
-     };
+    let creeper_strategy = game_state.creeper_strategy.clone();
+    let autopilot = game_state.autopilot.clone();
+    let handle_click_replay = {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        let seed = *seed;
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            start_game(game_config(&game_state), seed);
+        }
+    };
+
+    let handle_click_new_seed = {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            start_game(game_config(&game_state), new_seed());
+        }
+    };
+
+    let handle_click_level = |index: usize| {
+        let load_level = load_level.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            load_level(index);
+        }
+    };
+
+    let handle_click_levels = |open: bool| {
+        let show_levels = show_levels.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            if open {
+                game_state.dispatch(GameEvents::Pause);
+            }
+            show_levels.set(open);
+        }
+    };
+
+    let handle_click_daily = {
+        let start_game = start_game.clone();
+        let daily = daily.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let (year, month, day) = today();
+            start_game(daily_config(), daily_seed(year, month, day));
+            daily.set(Some(date_key(year, month, day)));
+        }
+    };
+
+    let handle_click_copy = {
+        let summary_ref = summary_ref.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            copy_text(&summary_ref);
+        }
+    };
+
+    // The first try of the day when this run came later.
+    let first_daily = (*daily)
+        .as_ref()
+        .and_then(|date| daily_log.get(date))
+        .filter(|first| Some(*first) != daily_result.as_ref())
+        .cloned();
+
+    let handle_click_stats = {
+        let show_stats = show_stats.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            game_state.dispatch(GameEvents::Pause);
+            show_stats.set(true);
+        }
+    };
+
+    let handle_click_free_play = {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            // Campaign and test levels have their own sizes, free play starts on the usual board.
+            let config = GameConfig { creepers: CREEPERS, rows: ROWS, columns: COLUMNS, ..game_config(&game_state) };
+            start_game(config, new_seed());
+        }
+    };
+
+    let score_breakdown: Vec<String> = game_state
+        .score_parts()
+        .into_iter()
+        .filter(|(_name, points)| *points != 0)
+        .map(|(name, points)| format!("{} {:+}", name, points))
+        .collect();
+    let score = html! {
+        <>
+            <span class="game_over_title">{format!("Score {}", game_state.score())}</span>
+            <span>{score_breakdown.join(", ")}</span>
+        </>
+    };
+
+    let share_query = SharedGame { seed: *seed, config: game_config(&game_state) }.to_query();
+
+    let handle_click_editor = |open: bool| {
+        let editing = editing.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            if open {
+                game_state.dispatch(GameEvents::Pause);
+            }
+            editing.set(open);
+        }
+    };
+
+    let handle_click_retry_draft = {
+        let play_draft = play_draft.clone();
+        let draft = (*draft).clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            play_draft(draft.clone());
+        }
+    };
+
+    let level_title = if *testing {
+        Some(format!("Testing {}", draft.name))
+    } else {
+        level.and_then(|index| {
+            pack.levels
+                .get(index)
+                .map(|current| format!("Level {}: {}", index + 1, current.name))
+        })
+    };
+    let par = level.and_then(|index| pack.levels.get(index)).map_or(0, |current| current.par);
+    let has_next_level = level.is_some_and(|index| index + 1 < pack.levels.len());
+
+    let handle_click_chase = {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let config = GameConfig { creeper_strategy: CreeperStrategy::Chase, ..game_config(&game_state) };
+            start_game(config, new_seed());
+        }
+    };
+
+    let handle_click_pack = {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let config = GameConfig { creeper_strategy: CreeperStrategy::Pack, ..game_config(&game_state) };
+            start_game(config, new_seed());
+        }
+    };
+
+    let handle_click_greedy = {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let config = GameConfig { autopilot: Autopilot::Greedy, ..game_config(&game_state) };
+            start_game(config, new_seed());
+        }
+    };
+
+    let handle_click_lookahead = {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let autopilot = Autopilot::Lookahead(LookaheadConfig::default());
+            let config = GameConfig { autopilot, ..game_config(&game_state) };
+            start_game(config, new_seed());
+        }
+    };
+
+    let handle_click_monte_carlo = {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let autopilot = Autopilot::MonteCarlo(MctsConfig::default());
+            let config = GameConfig { autopilot, ..game_config(&game_state) };
+            start_game(config, new_seed());
+        }
+    };
+
+    let handle_click_connectivity = |connectivity: Connectivity| {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            start_game(GameConfig { connectivity, ..game_config(&game_state) }, new_seed());
+        }
+    };
+
+    let handle_click_wrap = |wrap: bool| {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            start_game(GameConfig { wrap, ..game_config(&game_state) }, new_seed());
+        }
+    };
+
+    let handle_click_maze = |maze: Maze| {
+        let start_game = start_game.clone();
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            start_game(GameConfig { maze, ..game_config(&game_state) }, new_seed());
+        }
+    };
+
+    let handle_click_renderer = |selected: Renderer| {
+        let renderer = renderer.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            renderer.set(selected);
+        }
+    };
+
+    let is_paused = game_state.status == Status::Paused;
+    let handle_click_pause = {
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            game_state.dispatch(GameEvents::TogglePause);
+        }
+    };
+
+    let handle_click_step = {
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let tick = counter.borrow().wrapping_add(1);
+            *counter.borrow_mut() = tick;
+            game_state.dispatch(GameEvents::Step(tick));
+        }
+    };
+
+    let handle_speed = {
+        let tick_ms = tick_ms.clone();
+        move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<u32>() {
+                tick_ms.set(value.clamp(MIN_TICK_MS, MAX_TICK_MS));
+            }
+        }
+    };
+
+    let handle_click_direction = |direction: Direction| {
+        let game_state = game_state.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            game_state.dispatch(GameEvents::MoveFerris(direction));
+        }
+    };
+
+    let button_class = |selected: bool| {
+        if selected {
+            "strategy_button selected"
+        } else {
+            "strategy_button"
+        }
+    };
+
+    if *editing {
+        let on_change = {
+            let draft = draft.clone();
+            Callback::from(move |edited: Level| draft.set(edited))
+        };
+        let on_play = Callback::from(move |edited: Level| play_draft(edited));
+        let on_close = {
+            let editing = editing.clone();
+            Callback::from(move |_| editing.set(false))
+        };
+        return html! {
+            <LevelEditor level={(*draft).clone()} {on_change} {on_play} {on_close}/>
+        };
+    }
+
+    let stats_screen = if *show_stats {
+        let on_import = {
+            let stats = stats.clone();
+            Callback::from(move |imported: Stats| {
+                save_stats(&imported);
+                stats.set(imported);
+            })
+        };
+        let on_close = {
+            let show_stats = show_stats.clone();
+            Callback::from(move |_| show_stats.set(false))
+        };
+        html! { <StatsScreen stats={(*stats).clone()} {on_import} {on_close}/> }
+    } else {
+        html! { <></> }
+    };
 
     html! {
         <>
-            { if is_home { html! {
+            {stats_screen}
+            { if *show_levels { html! {
+                    <div class = "restart">
+                        <div class="game_over">
+                            <span class="game_over_title">{pack.name.clone()}</span>
+                            { for pack.levels.iter().enumerate().map(|(index, current)| {
+                                let best = progress.best(index).map(|best| format!(", best {}", best)).unwrap_or_default();
+                                let locked = if progress.is_unlocked(index) { "" } else { "🔒 " };
+                                html! {
+                                    <button class="restart_button" disabled={!progress.is_unlocked(index)} onclick={handle_click_level(index)}>
+                                        {format!("{}{}. {} (par {}{})", locked, index + 1, current.name, current.par, best)}
+                                    </button>
+                                }
+                            }) }
+                            <button class="restart_button" onclick={handle_click_levels(false)}>{"Back"}</button>
+                        </div>
+                    </div>
+                } } else if let (true, Some(result)) = (is_over, (*daily_result).clone()) { html! {
                     <div class = "restart" type="restart">
-                        <button class="restart_button" onclick={handle_click_restart}>{&RESTART_TEXT}</button>
+                        <div class="game_over">
+                            <span class="game_over_title">{instructions.clone()}</span>
+                            {score.clone()}
+                            <span>{format!("Daily challenge {}", result.date)}</span>
+                            <textarea class="level_text" rows="4" readonly=true ref={summary_ref.clone()} value={result.summary.clone()}/>
+                            <button class="restart_button" onclick={handle_click_copy}>{"Copy summary"}</button>
+                            { first_daily.map(|first| html! {
+                                <span>{format!("Only your first try counts today: {} in {} moves", if first.won { "won" } else { "lost" }, first.moves)}</span>
+                            }).unwrap_or_else(|| html! { <></> }) }
+                            <button class="restart_button" onclick={handle_click_new_seed.clone()}>{NEW_SEED_TEXT}</button>
+                        </div>
                     </div>
-                } } else { html! { <></> } } 
+                } } else if is_over && *testing { html! {
+                    <div class = "restart" type="restart">
+                        <div class="game_over">
+                            <span class="game_over_title">{instructions.clone()}</span>
+                            {score.clone()}
+                            <span>{format!("{} moves, par {}", game_state.ferris_moves(), draft.par)}</span>
+                            <button class="restart_button" onclick={handle_click_retry_draft}>{"Retry"}</button>
+                            <button class="restart_button" onclick={handle_click_editor(true)}>{"Edit level"}</button>
+                        </div>
+                    </div>
+                } } else if is_over { match (*level, level_title.clone()) {
+                    (Some(index), Some(title)) => html! {
+                        <div class = "restart" type="restart">
+                            <div class="game_over">
+                                <span class="game_over_title">{instructions.clone()}</span>
+                                {score.clone()}
+                                <span>{title}</span>
+                                <span>{format!("{} moves, par {}", game_state.ferris_moves(), par)}</span>
+                                { match (&game_state.status, has_next_level) {
+                                    (Status::Won, true) => html! {
+                                        <button class="restart_button" onclick={handle_click_level(index + 1)}>{"Next level"}</button>
+                                    },
+                                    (Status::Won, false) => html! { <span>{"Campaign complete!"}</span> },
+                                    _ => html! {
+                                        <button class="restart_button" onclick={handle_click_level(index)}>{"Retry level"}</button>
+                                    },
+                                } }
+                                <button class="restart_button" onclick={handle_click_levels(true)}>{"Levels"}</button>
+                            </div>
+                        </div>
+                    },
+                    _ => html! {
+                        <div class = "restart" type="restart">
+                            <div class="game_over">
+                                <span class="game_over_title">{instructions.clone()}</span>
+                                {score.clone()}
+                                <span>{format!("Seed {}", *seed)}</span>
+                                <a class="share_link" href={format!("?{}", share_query)}>{"Link to this board"}</a>
+                                <button class="restart_button" onclick={handle_click_replay}>{REPLAY_TEXT}</button>
+                                <button class="restart_button" onclick={handle_click_new_seed}>{NEW_SEED_TEXT}</button>
+                            </div>
+                        </div>
+                    },
+                } } else { html! { <></> } }
             }
             <div class="status">
-                <span class="center">{instructions}</span>
+                <span class="center">{level_title.map(|title| format!("{} - {}", title, instructions.clone())).unwrap_or(instructions)}</span>
+            </div>
+            <div class="strategy">
+                <span>{"Mode: "}</span>
+                <button class={button_class(level.is_none() && !*testing && daily.is_none())} onclick={handle_click_free_play}>{"Free play"}</button>
+                <button class={button_class(level.is_some())} onclick={handle_click_levels(true)}>{"Campaign"}</button>
+                <button class={button_class(daily.is_some())} onclick={handle_click_daily}>{"Daily"}</button>
+                <button class={button_class(*testing)} onclick={handle_click_editor(true)}>{"Editor"}</button>
+                <button class={button_class(*show_stats)} onclick={handle_click_stats}>{"Stats"}</button>
+                <span>{"Creepers: "}</span>
+                <button class={button_class(creeper_strategy == CreeperStrategy::Chase)} onclick={handle_click_chase}>{"Chase (easy)"}</button>
+                <button class={button_class(creeper_strategy == CreeperStrategy::Pack)} onclick={handle_click_pack}>{"Pack (hard)"}</button>
+                <span>{"Autopilot: "}</span>
+                <button class={button_class(autopilot == Autopilot::Greedy)} onclick={handle_click_greedy}>{"Greedy"}</button>
+                <button class={button_class(matches!(autopilot, Autopilot::Lookahead(_)))} onclick={handle_click_lookahead}>{"Lookahead"}</button>
+                <button class={button_class(matches!(autopilot, Autopilot::MonteCarlo(_)))} onclick={handle_click_monte_carlo}>{"Monte Carlo"}</button>
+                <span>{"Moves: "}</span>
+                <button class={button_class(game_state.connectivity == Connectivity::Four)} onclick={handle_click_connectivity(Connectivity::Four)}>{"4 ways"}</button>
+                <button class={button_class(game_state.connectivity == Connectivity::Eight)} onclick={handle_click_connectivity(Connectivity::Eight)}>{"8 ways"}</button>
+                <button class={button_class(game_state.connectivity == Connectivity::Hex)} onclick={handle_click_connectivity(Connectivity::Hex)}>{"Hex"}</button>
+                <span>{"Edges: "}</span>
+                <button class={button_class(!game_state.wrap)} onclick={handle_click_wrap(false)}>{"Walls"}</button>
+                <button class={button_class(game_state.wrap)} onclick={handle_click_wrap(true)}>{"Wrap"}</button>
+                <span>{"Maze: "}</span>
+                <button class={button_class(game_state.maze == Maze::Open)} onclick={handle_click_maze(Maze::Open)}>{"Open"}</button>
+                <button class={button_class(game_state.maze == Maze::Backtracker)} onclick={handle_click_maze(Maze::Backtracker)}>{"Backtracker"}</button>
+                <button class={button_class(game_state.maze == Maze::Prim)} onclick={handle_click_maze(Maze::Prim)}>{"Prim"}</button>
+                <button class={button_class(game_state.maze == Maze::Caves)} onclick={handle_click_maze(Maze::Caves)}>{"Caves"}</button>
+                <span>{"Board: "}</span>
+                <button class={button_class(*renderer == Renderer::Cells)} onclick={handle_click_renderer(Renderer::Cells)}>{"Cells"}</button>
+                <button class={button_class(*renderer == Renderer::Canvas)} onclick={handle_click_renderer(Renderer::Canvas)}>{"Canvas"}</button>
             </div>
-            <div class="grid">
-                {row_generator()}
+            <div class="strategy">
+                <button class={button_class(is_paused)} onclick={handle_click_pause}>{if is_paused { "Resume" } else { "Pause" }}</button>
+                <button class="strategy_button" disabled={!is_paused} onclick={handle_click_step}>{"Step"}</button>
+                <span>{"Tick: "}</span>
+                <input
+                    type="range"
+                    min={MIN_TICK_MS.to_string()}
+                    max={MAX_TICK_MS.to_string()}
+                    step="100"
+                    value={tick_ms.to_string()}
+                    oninput={handle_speed}
+                />
+                <span>{format!("{} ms", *tick_ms)}</span>
+            </div>
+            { match *renderer {
+                Renderer::Cells => html! {
+                    <div class="grid" style={grid_style(&game_state.board())}>
+                        {board_cells(&game_state.board())}
+                    </div>
+                },
+                Renderer::Canvas => html! { <CanvasBoard tick_ms={*tick_ms}/> },
+            } }
+            <div class="dpad">
+                <button class="dpad_button" disabled={!game_state.connectivity.allows(Direction::UpLeft)} onclick={handle_click_direction(Direction::UpLeft)}>{"◤"}</button>
+                <button class="dpad_button" disabled={!game_state.connectivity.allows(Direction::Up)} onclick={handle_click_direction(Direction::Up)}>{"▲"}</button>
+                <button class="dpad_button" disabled={!game_state.connectivity.allows(Direction::UpRight)} onclick={handle_click_direction(Direction::UpRight)}>{"◥"}</button>
+                <button class="dpad_button" disabled={!game_state.connectivity.allows(Direction::Left)} onclick={handle_click_direction(Direction::Left)}>{"◀"}</button>
+                <span/>
+                <button class="dpad_button" disabled={!game_state.connectivity.allows(Direction::Right)} onclick={handle_click_direction(Direction::Right)}>{"▶"}</button>
+                <button class="dpad_button" disabled={!game_state.connectivity.allows(Direction::DownLeft)} onclick={handle_click_direction(Direction::DownLeft)}>{"◣"}</button>
+                <button class="dpad_button" disabled={!game_state.connectivity.allows(Direction::Down)} onclick={handle_click_direction(Direction::Down)}>{"▼"}</button>
+                <button class="dpad_button" disabled={!game_state.connectivity.allows(Direction::DownRight)} onclick={handle_click_direction(Direction::DownRight)}>{"◢"}</button>
             </div>
         </>
     }
 }
 
-fn column_generator(column: i32) -> Vec<VNode> {
-    let rows: Vec<i32> = (0..ROWS).collect();
-    rows.iter()
-        .map(|row| {
+/**
+ * Size of the board for the CSS grid, see `.grid` in style.css.
+ */
+fn grid_style(board: &Board) -> String {
+    // Hex lines are shifted by half a cell, the grid has a column per half cell.
+    let width = match board.connectivity {
+        Connectivity::Hex => board.columns * 2 + 1,
+        Connectivity::Four | Connectivity::Eight => board.columns,
+    };
+    format!("--board-width: {}; --board-height: {};", width, board.rows)
+}
+
+/**
+ * Place of a hex in the grid, two half cells wide and shifted right on odd lines.
+ */
+fn hex_cell_style(location: &Location) -> String {
+    let (Row(row), Col(col)) = (location.row(), location.col());
+    format!(
+        "grid-column: {} / span 2; grid-row: {};",
+        col * 2 + (row & 1) + 1,
+        row + 1
+    )
+}
+
+fn board_cells(board: &Board) -> Vec<VNode> {
+    board
+        .locations()
+        .map(|location| {
             html! {
-                <Cell row={*row} column={column}/>
+                <Cell location={location}/>
             }
         })
         .collect()
 }
 
-fn row_generator() -> Vec<VNode> {
-    let rows: Vec<i32> = (0..COLUMNS).collect();
-    rows.iter()
-        .map(|j| {
+#[derive(PartialEq, Properties)]
+struct StatsScreenProps {
+    stats: Stats,
+    // Replaces the stats with ones exported from another browser.
+    on_import: Callback<Stats>,
+    on_close: Callback<()>,
+}
+
+#[function_component(StatsScreen)]
+fn stats_screen_component(props: &StatsScreenProps) -> Html {
+    // Contents of the export and import box.
+    let text = use_state(String::new);
+    let message = use_state(String::new);
+    let stats = &props.stats;
+    let overall = &stats.overall;
+
+    let handle_text = {
+        let text = text.clone();
+        move |event: InputEvent| {
+            let area: HtmlTextAreaElement = event.target_unchecked_into();
+            text.set(area.value());
+        }
+    };
+
+    let handle_click_export = {
+        let stats = stats.clone();
+        let text = text.clone();
+        let message = message.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            text.set(stats.to_json());
+            message.set("Copy the stats to the other browser and import them there".to_string());
+        }
+    };
+
+    let handle_click_import = {
+        let text = text.clone();
+        let message = message.clone();
+        let on_import = props.on_import.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            match Stats::from_json(&text) {
+                Ok(imported) => {
+                    message.set("Imported".to_string());
+                    on_import.emit(imported);
+                }
+                Err(err) => message.set(format!("Can not import: {}", err)),
+            }
+        }
+    };
+
+    let handle_click_close = {
+        let on_close = props.on_close.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            on_close.emit(());
+        }
+    };
+
+    let lines = [
+        format!("Games played: {}", overall.played),
+        format!("Win rate: {:.0}%", overall.win_rate() * 100f64),
+        format!(
+            "Fastest win: {}",
+            overall.fastest_win.map_or("-".to_string(), |moves| format!("{} moves", moves))
+        ),
+        format!("Longest survival: {} ticks", overall.longest_survival),
+        format!("Best score: {}", overall.best_score),
+        format!("Win streak: {} (best {})", stats.streak, stats.best_streak),
+    ];
+
+    html! {
+        <div class="restart">
+            <div class="game_over">
+                <span class="game_over_title">{"Stats"}</span>
+                { for lines.iter().map(|line| html! { <span>{line.clone()}</span> }) }
+                <span class="game_over_title">{"Best seeds"}</span>
+                { for stats.leaderboard(LEADERBOARD_SIZE).into_iter().map(|(seed, totals)| html! {
+                    <span>{format!("Seed {}: score {}, {} of {} won", seed, totals.best_score, totals.won, totals.played)}</span>
+                }) }
+                <textarea class="level_text" rows="4" value={(*text).clone()} oninput={handle_text}/>
+                <span>{(*message).clone()}</span>
+                <button class="restart_button" onclick={handle_click_export}>{"Export"}</button>
+                <button class="restart_button" onclick={handle_click_import}>{"Import"}</button>
+                <button class="restart_button" onclick={handle_click_close}>{"Back"}</button>
+            </div>
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+struct LevelEditorProps {
+    level: Level,
+    on_change: Callback<Level>,
+    // Test plays the level, only called when the map is well formed.
+    on_play: Callback<Level>,
+    on_close: Callback<()>,
+}
+
+#[function_component(LevelEditor)]
+fn level_editor_component(props: &LevelEditorProps) -> Html {
+    let tool = use_state(|| WALL);
+    // Contents of the export and import box.
+    let text = use_state(String::new);
+    let message = use_state(String::new);
+    let level = &props.level;
+    let board = level.board();
+    let (rows, columns) = level.size();
+
+    // Applies `edit` to a copy of the level and hands it to the owner of the level.
+    let edit = |edit: Box<dyn Fn(&mut Level)>| {
+        let level = level.clone();
+        let on_change = props.on_change.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let mut level = level.clone();
+            edit(&mut level);
+            on_change.emit(level);
+        }
+    };
+
+    let paint = |location: Location| {
+        let level = level.clone();
+        let on_change = props.on_change.clone();
+        let tool = *tool;
+        Callback::from(move |event: web_sys::MouseEvent| {
+            // Dragging with the button held paints every cell on the way.
+            if event.type_() == "mousedown" || event.buttons() == 1 {
+                event.prevent_default();
+                let mut level = level.clone();
+                level.paint(&location, tool);
+                on_change.emit(level);
+            }
+        })
+    };
+
+    let handle_size = |is_rows: bool| {
+        let level = level.clone();
+        let on_change = props.on_change.clone();
+        move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<usize>() {
+                let value = value.clamp(MIN_EDITOR_SIZE, MAX_EDITOR_SIZE);
+                let mut level = level.clone();
+                let (rows, columns) = level.size();
+                if is_rows {
+                    level.resize(value, columns);
+                } else {
+                    level.resize(rows, value);
+                }
+                on_change.emit(level);
+            }
+        }
+    };
+
+    let handle_name = {
+        let level = level.clone();
+        let on_change = props.on_change.clone();
+        move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            on_change.emit(Level { name: input.value(), ..level.clone() });
+        }
+    };
+
+    let handle_par = {
+        let level = level.clone();
+        let on_change = props.on_change.clone();
+        move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            if let Ok(par) = input.value().parse::<usize>() {
+                on_change.emit(Level { par, ..level.clone() });
+            }
+        }
+    };
+
+    let handle_click_tool = |selected: char| {
+        let tool = tool.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            tool.set(selected);
+        }
+    };
+
+    let handle_text = {
+        let text = text.clone();
+        move |event: InputEvent| {
+            let area: HtmlTextAreaElement = event.target_unchecked_into();
+            text.set(area.value());
+        }
+    };
+
+    // Only levels Ferris can win are exported.
+    let handle_click_export = |json: bool| {
+        let level = level.clone();
+        let text = text.clone();
+        let message = message.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            match level.check() {
+                Ok(steps) => {
+                    text.set(if json { level.to_json() } else { level.to_text() });
+                    message.set(format!("Ferris can get home in {} moves", steps));
+                }
+                Err(err) => message.set(format!("Can not export: {}", err)),
+            }
+        }
+    };
+
+    // JSON brings its settings along, a plain map keeps the settings of the editor.
+    let handle_click_import = {
+        let level = level.clone();
+        let text = text.clone();
+        let message = message.clone();
+        let on_change = props.on_change.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            let imported = if text.trim_start().starts_with('{') {
+                Level::from_json(&text)
+            } else {
+                Ok(level.with_text(&text))
+            };
+            match imported.and_then(|imported| imported.layout(&Autopilot::Greedy).map(|_| imported)) {
+                Ok(imported) => {
+                    message.set("Imported".to_string());
+                    on_change.emit(imported);
+                }
+                Err(err) => message.set(format!("Can not import: {}", err)),
+            }
+        }
+    };
+
+    let handle_click_play = {
+        let level = level.clone();
+        let message = message.clone();
+        let on_play = props.on_play.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            match level.layout(&Autopilot::Greedy) {
+                Ok(_) => on_play.emit(level.clone()),
+                Err(err) => message.set(format!("Can not play: {}", err)),
+            }
+        }
+    };
+
+    let handle_click_close = {
+        let on_close = props.on_close.clone();
+        move |event: web_sys::MouseEvent| {
+            event.prevent_default();
+            on_close.emit(());
+        }
+    };
+
+    let button_class = |selected: bool| {
+        if selected {
+            "strategy_button selected"
+        } else {
+            "strategy_button"
+        }
+    };
+
+    let cells: Html = board
+        .locations()
+        .map(|location| {
+            let (class, style) = match board.connectivity {
+                Connectivity::Hex => (classes!("cell", "hex_cell"), hex_cell_style(&location)),
+                Connectivity::Four | Connectivity::Eight => (classes!("cell"), String::new()),
+            };
+            let cell = level.cell(&location);
+            let class = match cell {
+                WALL => classes!(class, "wall_cell"),
+                CHASER => classes!(class, "chaser_cell"),
+                PACKER => classes!(class, "packer_cell"),
+                _ => class,
+            };
+            let image = match cell {
+                FERRIS => html! { <img width="100%" draggable="false" src="thumbnail/sadferris.png"/> },
+                HOME => html! { <img width="100%" draggable="false" src="thumbnail/home.png"/> },
+                CREEPER | CHASER | PACKER => html! { <img width="100%" draggable="false" src="thumbnail/creeper2.png"/> },
+                _ => html! { <></> },
+            };
+            let onpaint = paint(location);
             html! {
-                <>
-                    {column_generator(*j)}
-                </>
+                <div class={class} style={style} onmousedown={onpaint.clone()} onmouseenter={onpaint}>
+                    {image}
+                </div>
             }
         })
-        .collect()
+        .collect();
+
+    html! {
+        <>
+            <div class="status">
+                <span class="center">{"Level editor, paint the board and test play it"}</span>
+            </div>
+            <div class="strategy">
+                <span>{"Paint: "}</span>
+                { for EDITOR_TOOLS.iter().map(|(cell, label)| html! {
+                    <button class={button_class(*tool == *cell)} onclick={handle_click_tool(*cell)}>{*label}</button>
+                }) }
+                <span>{"Rows: "}</span>
+                <input type="number" min={MIN_EDITOR_SIZE.to_string()} max={MAX_EDITOR_SIZE.to_string()} value={rows.to_string()} onchange={handle_size(true)}/>
+                <span>{"Columns: "}</span>
+                <input type="number" min={MIN_EDITOR_SIZE.to_string()} max={MAX_EDITOR_SIZE.to_string()} value={columns.to_string()} onchange={handle_size(false)}/>
+                <span>{"Creepers: "}</span>
+                <button class={button_class(level.creeper_strategy == "chase")} onclick={edit(Box::new(|level| level.creeper_strategy = "chase".to_string()))}>{"Chase"}</button>
+                <button class={button_class(level.creeper_strategy == "pack")} onclick={edit(Box::new(|level| level.creeper_strategy = "pack".to_string()))}>{"Pack"}</button>
+                <span>{"Moves: "}</span>
+                <button class={button_class(board.connectivity == Connectivity::Four)} onclick={edit(Box::new(|level| level.connectivity = "four".to_string()))}>{"4 ways"}</button>
+                <button class={button_class(board.connectivity == Connectivity::Eight)} onclick={edit(Box::new(|level| level.connectivity = "eight".to_string()))}>{"8 ways"}</button>
+                <button class={button_class(board.connectivity == Connectivity::Hex)} onclick={edit(Box::new(|level| level.connectivity = "hex".to_string()))}>{"Hex"}</button>
+                <span>{"Edges: "}</span>
+                <button class={button_class(!level.wrap)} onclick={edit(Box::new(|level| level.wrap = false))}>{"Walls"}</button>
+                <button class={button_class(level.wrap)} onclick={edit(Box::new(|level| level.wrap = true))}>{"Wrap"}</button>
+            </div>
+            <div class="strategy">
+                <span>{"Name: "}</span>
+                <input type="text" value={level.name.clone()} onchange={handle_name}/>
+                <span>{"Par: "}</span>
+                <input type="number" min="0" value={level.par.to_string()} onchange={handle_par}/>
+                <button class="strategy_button" onclick={handle_click_play}>{"Test play"}</button>
+                <button class="strategy_button" onclick={handle_click_export(false)}>{"Export text"}</button>
+                <button class="strategy_button" onclick={handle_click_export(true)}>{"Export JSON"}</button>
+                <button class="strategy_button" onclick={handle_click_import}>{"Import"}</button>
+                <button class="strategy_button" onclick={handle_click_close}>{"Close"}</button>
+                <span>{(*message).clone()}</span>
+            </div>
+            <div class="strategy">
+                <textarea class="level_text" rows="6" value={(*text).clone()} oninput={handle_text}/>
+            </div>
+            <div class="grid" style={grid_style(&board)}>
+                {cells}
+            </div>
+        </>
+    }
 }
 
 #[function_component(App)]
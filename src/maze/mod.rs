@@ -0,0 +1,287 @@
+use crate::board::Board;
+use crate::model::Location;
+use anyhow::anyhow;
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    str::FromStr,
+};
+
+// Share of the cells that start as walls before caves are smoothed.
+const CAVE_FILL: f64 = 0.45;
+// Smoothing passes of the caves automaton.
+const CAVE_PASSES: usize = 4;
+// A cave cell becomes a wall when at least this many of its 8 surrounding cells are.
+const CAVE_WALL_NEIGHBORS: usize = 5;
+
+/**
+ * How the walls of a board are laid out.
+ * Use Open for a board without walls.
+ * Use Backtracker for long winding corridors carved by a randomized depth first search.
+ * Use Prim for a maze with many short dead ends grown by randomized Prim's algorithm.
+ * Use Caves for open caverns smoothed out of noise by a cellular automaton.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Maze {
+    #[default]
+    Open,
+    Backtracker,
+    Prim,
+    Caves,
+}
+
+impl FromStr for Maze {
+    type Err = anyhow::Error;
+
+    fn from_str(maze: &str) -> Result<Self, Self::Err> {
+        match maze.to_lowercase().as_str() {
+            "open" | "none" => Ok(Maze::Open),
+            "backtracker" | "recursive_backtracker" => Ok(Maze::Backtracker),
+            "prim" | "prims" => Ok(Maze::Prim),
+            "caves" | "cave" => Ok(Maze::Caves),
+            _ => Err(anyhow!(
+                "unknown maze {:?}, use open, backtracker, prim or caves",
+                maze
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Maze::Open => write!(f, "open"),
+            Maze::Backtracker => write!(f, "backtracker"),
+            Maze::Prim => write!(f, "prim"),
+            Maze::Caves => write!(f, "caves"),
+        }
+    }
+}
+
+impl Maze {
+    /**
+     * Walls of a new board picked by `randy`. The open cells are all connected to each
+     * other, so whatever open cells Ferris and home spawn on there is a path between them.
+     */
+    pub fn walls<R: Rng>(&self, board: &Board, randy: &mut R) -> HashSet<Location> {
+        let walls = match self {
+            Maze::Open => return HashSet::new(),
+            Maze::Backtracker => backtracker(board, randy),
+            Maze::Prim => prim(board, randy),
+            Maze::Caves => caves(board, randy),
+        };
+        keep_largest_region(board, walls)
+    }
+}
+
+/**
+ * Cells of the maze lattice, rooms sit on even rows and columns and the cells between
+ * two rooms are walls until a passage is carved through them.
+ */
+fn rooms(board: &Board) -> Vec<Location> {
+    board
+        .locations()
+        .filter(|location| location.row().0 % 2 == 0 && location.col().0 % 2 == 0)
+        .collect()
+}
+
+/**
+ * Rooms two cells away from `room`, with the cell between them.
+ */
+fn adjacent_rooms(board: &Board, room: &Location) -> Vec<(Location, Location)> {
+    [(-2, 0), (0, -2), (0, 2), (2, 0)]
+        .iter()
+        .map(|(d_row, d_col)| {
+            (
+                room.offset(*d_row, *d_col),
+                room.offset(d_row / 2, d_col / 2),
+            )
+        })
+        .filter(|(next, _between)| board.contains(next))
+        .collect()
+}
+
+fn all_walls(board: &Board) -> HashSet<Location> {
+    board.locations().collect()
+}
+
+fn backtracker<R: Rng>(board: &Board, randy: &mut R) -> HashSet<Location> {
+    let mut walls = all_walls(board);
+    let start = match rooms(board).choose(randy) {
+        Some(start) => start.clone(),
+        None => return walls,
+    };
+    walls.remove(&start);
+    let mut stack = vec![start];
+    while let Some(room) = stack.last().cloned() {
+        let unvisited: Vec<(Location, Location)> = adjacent_rooms(board, &room)
+            .into_iter()
+            .filter(|(next, _between)| walls.contains(next))
+            .collect();
+        match unvisited.choose(randy) {
+            Some((next, between)) => {
+                walls.remove(between);
+                walls.remove(next);
+                stack.push(next.clone());
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    walls
+}
+
+fn prim<R: Rng>(board: &Board, randy: &mut R) -> HashSet<Location> {
+    let mut walls = all_walls(board);
+    let start = match rooms(board).choose(randy) {
+        Some(start) => start.clone(),
+        None => return walls,
+    };
+    walls.remove(&start);
+    let mut frontier = adjacent_rooms(board, &start);
+    while !frontier.is_empty() {
+        let (next, between) = frontier.swap_remove(randy.gen_range(0..frontier.len()));
+        if !walls.contains(&next) {
+            continue;
+        }
+        walls.remove(&between);
+        walls.remove(&next);
+        frontier.extend(
+            adjacent_rooms(board, &next)
+                .into_iter()
+                .filter(|(room, _between)| walls.contains(room)),
+        );
+    }
+    walls
+}
+
+fn caves<R: Rng>(board: &Board, randy: &mut R) -> HashSet<Location> {
+    let mut walls: HashSet<Location> = board
+        .locations()
+        .filter(|_location| randy.gen_bool(CAVE_FILL))
+        .collect();
+    for _pass in 0..CAVE_PASSES {
+        walls = board
+            .locations()
+            .filter(|location| {
+                // Cells off the board count as walls so caves do not hug the edges.
+                let wall_neighbors = (-1..=1)
+                    .flat_map(|d_row| (-1..=1).map(move |d_col| (d_row, d_col)))
+                    .filter(|offset| *offset != (0, 0))
+                    .map(|(d_row, d_col)| location.offset(d_row, d_col))
+                    .filter(|neighbor| !board.contains(neighbor) || walls.contains(neighbor))
+                    .count();
+                wall_neighbors >= CAVE_WALL_NEIGHBORS
+            })
+            .collect();
+    }
+    walls
+}
+
+/**
+ * Fills every pocket of open cells but the largest one, a board where every cell is a
+ * wall is left open instead.
+ */
+fn keep_largest_region(board: &Board, mut walls: HashSet<Location>) -> HashSet<Location> {
+    let mut seen: HashSet<Location> = HashSet::new();
+    let mut largest: Vec<Location> = vec![];
+    for location in board.locations() {
+        if walls.contains(&location) || seen.contains(&location) {
+            continue;
+        }
+        let mut region = vec![location.clone()];
+        seen.insert(location.clone());
+        let mut queue = VecDeque::from([location]);
+        while let Some(cell) = queue.pop_front() {
+            for neighbor in board.neighbors(&cell) {
+                if !walls.contains(&neighbor) && seen.insert(neighbor.clone()) {
+                    region.push(neighbor.clone());
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        if region.len() > largest.len() {
+            largest = region;
+        }
+    }
+    if largest.is_empty() {
+        return HashSet::new();
+    }
+    let largest: HashSet<Location> = largest.into_iter().collect();
+    walls.extend(board.locations().filter(|cell| !largest.contains(cell)));
+    walls
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        board::{Board, Col, Row},
+        model::{Connectivity, Location},
+    };
+
+    use super::Maze;
+
+    fn open_cells_are_connected(board: &Board, walls: &HashSet<Location>) -> bool {
+        let open: Vec<_> = board
+            .locations()
+            .filter(|location| !walls.contains(location))
+            .collect();
+        let mut seen: HashSet<Location> = open.iter().take(1).cloned().collect();
+        let mut queue: VecDeque<_> = open.iter().take(1).cloned().collect();
+        while let Some(cell) = queue.pop_front() {
+            for neighbor in board.neighbors(&cell) {
+                if !walls.contains(&neighbor) && seen.insert(neighbor.clone()) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        seen.len() == open.len()
+    }
+
+    #[test]
+    fn mazes_are_connected() {
+        for connectivity in [Connectivity::Four, Connectivity::Eight, Connectivity::Hex] {
+            let board = Board::new(12, 24, connectivity);
+            for maze in [Maze::Backtracker, Maze::Prim, Maze::Caves] {
+                for seed in 0..5 {
+                    let walls = maze.walls(&board, &mut StdRng::seed_from_u64(seed));
+                    assert!(!walls.is_empty(), "{:?} has no walls", maze);
+                    assert!(walls.len() < board.len(), "{:?} has no open cell", maze);
+                    assert!(open_cells_are_connected(&board, &walls), "{:?}", maze);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mazes_follow_the_seed() {
+        let board = Board::new(12, 24, Connectivity::Eight);
+        for maze in [Maze::Backtracker, Maze::Prim, Maze::Caves] {
+            let walls = |seed| maze.walls(&board, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(walls(7), walls(7));
+            assert_ne!(walls(7), walls(8));
+        }
+        assert!(Maze::Open
+            .walls(&board, &mut StdRng::seed_from_u64(7))
+            .is_empty());
+    }
+
+    #[test]
+    fn perfect_mazes_carve_the_room_lattice() {
+        let board = Board::new(7, 9, Connectivity::Four);
+        for maze in [Maze::Backtracker, Maze::Prim] {
+            let walls = maze.walls(&board, &mut StdRng::seed_from_u64(3));
+            // Rooms are open, pillars between four rooms stay walls.
+            assert!(!walls.contains(&Location::at(Row(2), Col(4))));
+            assert!(walls.contains(&Location::at(Row(3), Col(3))));
+            // A perfect maze of 5 by 4 rooms has 19 passages.
+            assert_eq!(board.len() - walls.len(), 20 + 19);
+        }
+    }
+}
@@ -0,0 +1,450 @@
+use crate::autopilot::{now_ms, MoveStats};
+use crate::dijkstra::Mode;
+use crate::hunt::TICKS_PER_CREEPER_MOVE;
+use crate::model::{Game, GameState, Location};
+use anyhow::Result;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+/**
+ * Settings of the Monte Carlo tree search autopilot.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct MctsConfig {
+    // Number of playouts per move, every playout adds one node to the search tree. This
+    // is what bounds the search, keep it small enough to fit in a tick.
+    pub iterations: u32,
+    // How many ticks a playout runs for before its outcome is estimated.
+    pub playout_ticks: u32,
+    // Weight of the exploration term of UCB1, higher values try more moves.
+    pub exploration: f64,
+    // Safety net on slow machines, playouts stop early once this many milliseconds went
+    // by and the search ends. The first iteration always runs.
+    pub time_budget_ms: u32,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            iterations: 16,
+            playout_ticks: 4,
+            exploration: 1.4,
+            time_budget_ms: 300,
+        }
+    }
+}
+
+// Reward of getting home right away, every tick it takes costs WIN_DISCOUNT.
+const WIN_REWARD: f64 = 1.0;
+const WIN_DISCOUNT: f64 = 0.01;
+// Best reward of a playout that ends before Ferris gets home.
+const UNFINISHED_REWARD: f64 = 0.8;
+// Chance that Ferris heads straight home during a playout instead of moving at random.
+const PLAYOUT_GREEDINESS: f64 = 0.7;
+
+struct Node {
+    state: GameState,
+    // Tick Ferris moves on next, the creepers already moved for it.
+    tick: i16,
+    // Ticks between the root and this node.
+    depth: u32,
+    // Set when the game is over.
+    reward: Option<f64>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Location>,
+    visits: u32,
+    total_reward: f64,
+}
+
+struct Tree<'a> {
+    game: Game,
+    config: &'a MctsConfig,
+    home_distance: HashMap<Location, i32>,
+    nodes: Vec<Node>,
+    randy: StdRng,
+    // `now_ms` after which playouts give up and estimate the outcome.
+    deadline: f64,
+}
+
+/**
+ * Runs `config.iterations` rounds of Monte Carlo tree search from `state`, fewer when
+ * they do not fit in `config.time_budget_ms`. `tick` is the tick Ferris is about to move on.
+ * Creepers move like they do in the real game, while Ferris moves towards home most of
+ * the time during random playouts.
+ *
+ * Returns how often each of Ferris's moves was visited and how well it did. The
+ * search is seeded from the tick and Ferris's location so replays are deterministic.
+ */
+pub fn search(
+    game: &Game,
+    state: &GameState,
+    tick: i16,
+    config: &MctsConfig,
+) -> Result<Vec<MoveStats>> {
+    let deadline = now_ms() + config.time_budget_ms as f64;
+    let ferris = &state.ferris.location;
+    let seed = ((tick as u64) << 32) ^ ((ferris.col().0 as u64) << 16) ^ (ferris.row().0 as u64);
+    let game = game.with_state(state.clone());
+    let mut tree = Tree {
+        home_distance: game.steps_to(&game.target),
+        game,
+        config,
+        nodes: vec![],
+        randy: StdRng::seed_from_u64(seed),
+        deadline,
+    };
+    tree.add_node(state.clone(), tick, 0, None, None);
+    for iteration in 0..config.iterations {
+        if iteration > 0 && now_ms() > tree.deadline {
+            break;
+        }
+        tree.iterate()?;
+    }
+    Ok(tree.nodes[0]
+        .children
+        .iter()
+        .map(|child| {
+            let child = &tree.nodes[*child];
+            MoveStats {
+                location: child.state.ferris.location.clone(),
+                visits: child.visits,
+                value: child.total_reward / child.visits.max(1) as f64,
+            }
+        })
+        .collect())
+}
+
+/**
+ * The move picked by the search, the most visited one.
+ */
+pub fn most_visited(move_stats: &[MoveStats]) -> Option<Location> {
+    move_stats
+        .iter()
+        .max_by(|a, b| a.visits.cmp(&b.visits).then(a.value.total_cmp(&b.value)))
+        .map(|stats| stats.location.clone())
+}
+
+impl<'a> Tree<'a> {
+    fn add_node(
+        &mut self,
+        state: GameState,
+        tick: i16,
+        depth: u32,
+        reward: Option<f64>,
+        parent: Option<usize>,
+    ) -> usize {
+        let mut untried = if reward.is_none() {
+            self.moves_from(&state.ferris.location)
+        } else {
+            vec![]
+        };
+        // Expand the moves closest to home first.
+        untried.sort_by_key(|location| -self.home_distance(location));
+        self.nodes.push(Node {
+            state,
+            tick,
+            depth,
+            reward,
+            parent,
+            children: vec![],
+            untried,
+            visits: 0,
+            total_reward: 0f64,
+        });
+        let index = self.nodes.len() - 1;
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(index);
+        }
+        index
+    }
+
+    fn iterate(&mut self) -> Result<()> {
+        // Selection, walk down fully expanded nodes.
+        let mut index = 0;
+        while self.nodes[index].reward.is_none()
+            && self.nodes[index].untried.is_empty()
+            && !self.nodes[index].children.is_empty()
+        {
+            index = self.best_child(index);
+        }
+        // Expansion, skipped once time is up as long as the root has a move to pick.
+        if now_ms() > self.deadline && !self.nodes[0].children.is_empty() {
+            return Ok(());
+        }
+        if let Some(next) = self.nodes[index].untried.pop() {
+            let node = &self.nodes[index];
+            let (state, tick, reward) = self.advance(&node.state, node.tick, node.depth, &next)?;
+            let depth = node.depth + 1;
+            index = self.add_node(state, tick, depth, reward, Some(index));
+        }
+        // Simulation
+        let node = &self.nodes[index];
+        let reward = match node.reward {
+            Some(reward) => reward,
+            None => {
+                let (state, tick, depth) = (node.state.clone(), node.tick, node.depth);
+                self.playout(state, tick, depth)?
+            }
+        };
+        // Backpropagation
+        let mut current = Some(index);
+        while let Some(index) = current {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            node.total_reward += reward;
+            current = node.parent;
+        }
+        Ok(())
+    }
+
+    /**
+     * UCB1, balances moves that did well with moves that were barely tried.
+     */
+    fn best_child(&self, index: usize) -> usize {
+        let parent_visits = (self.nodes[index].visits.max(1) as f64).ln();
+        let score = |child: &usize| {
+            let child = &self.nodes[*child];
+            let visits = child.visits.max(1) as f64;
+            child.total_reward / visits + self.config.exploration * (parent_visits / visits).sqrt()
+        };
+        *self.nodes[index]
+            .children
+            .iter()
+            .max_by(|a, b| score(a).total_cmp(&score(b)))
+            .unwrap_or(&index)
+    }
+
+    /**
+     * Moves Ferris to `next` and then lets creepers move if it is their turn. Returns the
+     * new state, the next tick and the reward if the game is over.
+     */
+    fn advance(
+        &self,
+        state: &GameState,
+        tick: i16,
+        depth: u32,
+        next: &Location,
+    ) -> Result<(GameState, i16, Option<f64>)> {
+        let mut state = state.clone();
+        state.ferris.location = next.clone();
+        if *next == self.game.target {
+            let reward = WIN_REWARD - WIN_DISCOUNT * (depth + 1) as f64;
+            return Ok((state, tick, Some(reward)));
+        }
+        if state
+            .creepers
+            .iter()
+            .any(|creeper| creeper.location == *next)
+        {
+            return Ok((state, tick, Some(0f64)));
+        }
+        let tick = tick.wrapping_add(1);
+        if tick as i32 % TICKS_PER_CREEPER_MOVE == 0 {
+            state.creepers = self.game.move_creepers(&state)?;
+        }
+        Ok((state, tick, None))
+    }
+
+    fn playout(&mut self, mut state: GameState, mut tick: i16, mut depth: u32) -> Result<f64> {
+        for _playout_tick in 0..self.config.playout_ticks {
+            // Creeper moves are the slow part of a playout, stop on time and estimate.
+            if now_ms() > self.deadline {
+                break;
+            }
+            let moves = self.moves_from(&state.ferris.location);
+            let next = if self.randy.gen_bool(PLAYOUT_GREEDINESS) {
+                moves
+                    .iter()
+                    .min_by_key(|location| self.home_distance(location))
+                    .cloned()
+            } else {
+                moves.get(self.randy.gen_range(0..moves.len())).cloned()
+            };
+            let next = match next {
+                Some(next) => next,
+                None => break,
+            };
+            let (next_state, next_tick, reward) = self.advance(&state, tick, depth, &next)?;
+            if let Some(reward) = reward {
+                return Ok(reward);
+            }
+            state = next_state;
+            tick = next_tick;
+            depth += 1;
+        }
+        let longest = (self.game.rows + self.game.columns).max(1) as f64;
+        let progress = 1f64 - (self.home_distance(&state.ferris.location) as f64 / longest);
+        Ok(UNFINISHED_REWARD * progress.clamp(0f64, 1f64))
+    }
+
+    /**
+     * All the cells Ferris can reach in one move, including staying put.
+     */
+    fn moves_from(&self, location: &Location) -> Vec<Location> {
+        let mut moves =
+            self.game
+                .get_adjacent_vertices(location, &self.game.target, &Mode::Creeper);
+        moves.push(location.clone());
+        moves
+    }
+
+    fn home_distance(&self, location: &Location) -> i32 {
+        self.home_distance
+            .get(location)
+            .copied()
+            .unwrap_or(self.game.rows * self.game.columns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        autopilot::{Autopilot, MoveStats},
+        board::{Col, Row},
+        hunt::CreeperStrategy,
+        maze::Maze,
+        model::{Connectivity, Creeper, Ferris, Game, GameConfig, GameState, Location, Status},
+    };
+
+    use super::{most_visited, search, MctsConfig};
+
+    fn game_with(ferris: Location, creepers: Vec<Location>, target: Location) -> Game {
+        Game {
+            moves: vec![GameState {
+                creepers: creepers
+                    .into_iter()
+                    .map(|location| Creeper { location })
+                    .collect(),
+                ferris: Ferris {
+                    location: ferris,
+                    path: vec![],
+                },
+            }],
+            target,
+            status: Status::Playing,
+            autopilot: Autopilot::MonteCarlo(MctsConfig::default()),
+            ..Game::empty(8, 8)
+        }
+    }
+
+    #[test]
+    fn mcts_goes_home() {
+        let game = game_with(
+            Location::at(Row(3), Col(3)),
+            vec![Location::at(Row(6), Col(6))],
+            Location::at(Row(2), Col(2)),
+        );
+        let config = MctsConfig {
+            iterations: 30,
+            time_budget_ms: u32::MAX,
+            ..MctsConfig::default()
+        };
+        let move_stats = search(&game, game.moves.last().unwrap(), 1, &config).unwrap();
+        assert_eq!(
+            most_visited(&move_stats),
+            Some(Location::at(Row(2), Col(2)))
+        );
+    }
+
+    #[test]
+    fn mcts_visits_add_up_to_iterations() {
+        let game = game_with(
+            Location::at(Row(0), Col(0)),
+            vec![Location::at(Row(4), Col(4))],
+            Location::at(Row(0), Col(7)),
+        );
+        let config = MctsConfig {
+            iterations: 40,
+            time_budget_ms: u32::MAX,
+            ..MctsConfig::default()
+        };
+        let move_stats = search(&game, game.moves.last().unwrap(), 1, &config).unwrap();
+        // Ferris in a corner has three neighbors and can stay put.
+        assert_eq!(move_stats.len(), 4);
+        let visits: u32 = move_stats.iter().map(|stats| stats.visits).sum();
+        assert_eq!(visits, config.iterations);
+        let again = search(&game, game.moves.last().unwrap(), 1, &config).unwrap();
+        assert_eq!(move_stats, again);
+    }
+
+    #[test]
+    fn mcts_without_time_still_moves() {
+        let game = game_with(
+            Location::at(Row(0), Col(0)),
+            vec![Location::at(Row(7), Col(7))],
+            Location::at(Row(5), Col(0)),
+        );
+        let config = MctsConfig {
+            iterations: 100_000,
+            time_budget_ms: 0,
+            ..MctsConfig::default()
+        };
+        let move_stats = search(&game, game.moves.last().unwrap(), 1, &config).unwrap();
+        let visits: u32 = move_stats.iter().map(|stats| stats.visits).sum();
+        assert!((1..config.iterations).contains(&visits));
+        assert!(most_visited(&move_stats).is_some());
+    }
+
+    fn full_board() -> Game {
+        let config = GameConfig {
+            creepers: 10,
+            rows: 12,
+            columns: 24,
+            creeper_strategy: CreeperStrategy::Pack,
+            autopilot: Autopilot::MonteCarlo(MctsConfig::default()),
+            connectivity: Connectivity::Eight,
+            wrap: false,
+            maze: Maze::Open,
+        };
+        Game::new(&config, &mut StdRng::seed_from_u64(3))
+    }
+
+    #[test]
+    fn mcts_stops_at_its_iterations() {
+        let game = full_board();
+        let config = MctsConfig {
+            time_budget_ms: u32::MAX,
+            ..MctsConfig::default()
+        };
+        let move_stats = search(&game, game.moves.last().unwrap(), 1, &config).unwrap();
+        let visits: u32 = move_stats.iter().map(|stats| stats.visits).sum();
+        assert_eq!(visits, config.iterations);
+    }
+
+    // Debug builds are many times slower than the game that ships, only time releases.
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn mcts_fits_in_a_tick() {
+        let game = full_board();
+        let start = super::now_ms();
+        let move_stats =
+            search(&game, game.moves.last().unwrap(), 1, &MctsConfig::default()).unwrap();
+        // The browser game ticks every 500ms.
+        assert!(super::now_ms() - start < 500f64);
+        assert!(most_visited(&move_stats).is_some());
+    }
+
+    #[test]
+    fn most_visited_picks_the_busiest_move() {
+        let move_stats = vec![
+            MoveStats {
+                location: Location::at(Row(1), Col(0)),
+                visits: 3,
+                value: 0.9,
+            },
+            MoveStats {
+                location: Location::at(Row(1), Col(1)),
+                visits: 7,
+                value: 0.2,
+            },
+        ];
+        assert_eq!(
+            most_visited(&move_stats),
+            Some(Location::at(Row(1), Col(1)))
+        );
+        assert_eq!(most_visited(&[]), None);
+    }
+}
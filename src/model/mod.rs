@@ -1,71 +1,306 @@
+use crate::autopilot::{Autopilot, MoveStats};
+use crate::board::{axial, hex_center, Board, Col, Row};
 use crate::dijkstra::{Dijkstra, Mode};
+use crate::hunt::{assign_goals, CreeperStrategy, TICKS_PER_CREEPER_MOVE};
+use crate::maze::Maze;
+use anyhow::{anyhow, Result};
 use gloo_console::log;
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::{
-    collections::HashMap,
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt::{self},
     rc::Rc,
+    str::FromStr,
 };
 use yew::Reducible;
 
-pub type VertexId = (i32, i32);
-
-#[derive(Clone, Debug, PartialEq)]
+/**
+ * A cell of the board, `Location::at(Row(2), Col(5))` is the sixth cell of the third
+ * line from the top.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Location {
-    pub x: i32,
-    pub y: i32,
+    row: Row,
+    col: Col,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Direction {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
-impl Location {
-    pub fn id(&self) -> VertexId {
-        (self.x, self.y)
+/**
+ * Directions by name, case does not matter: up, down, left, right, upleft, upright,
+ * downleft and downright, the diagonals also take an underscore like up_left.
+ */
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(direction: &str) -> Result<Self, Self::Err> {
+        match direction.to_lowercase().as_str() {
+            "up" => Ok(Direction::Up),
+            "down" => Ok(Direction::Down),
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            "upleft" | "up_left" => Ok(Direction::UpLeft),
+            "upright" | "up_right" => Ok(Direction::UpRight),
+            "downleft" | "down_left" => Ok(Direction::DownLeft),
+            "downright" | "down_right" => Ok(Direction::DownRight),
+            _ => Err(anyhow!(
+                "unknown direction {:?}, use up, down, left, right, upleft, upright, downleft or downright",
+                direction
+            )),
+        }
     }
+}
 
-    pub fn from(x: i32, y: i32) -> Location {
-        Location { x, y }
+impl Direction {
+    /**
+     * How many rows and columns a step in this direction goes, in that order.
+     */
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (-1, 1),
+            Direction::DownLeft => (1, -1),
+            Direction::DownRight => (1, 1),
+        }
     }
 
-    pub fn move_direction(&self, direction: Direction, width: i32, height: i32) -> Location {
-        match direction {
-            Direction::Up => Some(Location {
-                x: self.x,
-                y: self.y - 1,
-            })
-            .filter(|_s| self.y > 0),
-            Direction::Left => Some(Location {
-                x: self.x - 1,
-                y: self.y,
+    /**
+     * Direction of a swipe that moved `dx`, `dy` pixels on screen, None when the finger
+     * barely moved.
+     */
+    pub fn from_swipe(dx: f64, dy: f64) -> Option<Direction> {
+        const MIN_SWIPE_PX: f64 = 30f64;
+        if dx.abs().max(dy.abs()) < MIN_SWIPE_PX {
+            None
+        } else if dx.abs() > dy.abs() {
+            Some(if dx > 0f64 {
+                Direction::Right
+            } else {
+                Direction::Left
             })
-            .filter(|_s| self.x > 0),
-            Direction::Right => Some(Location {
-                x: self.x + 1,
-                y: self.y,
-            })
-            .filter(|_s| self.x < width - 1),
-            Direction::Down => Some(Location {
-                x: self.x,
-                y: self.y + 1,
-            })
-            .filter(|_s| self.y < height - 1),
+        } else if dy > 0f64 {
+            Some(Direction::Down)
+        } else {
+            Some(Direction::Up)
+        }
+    }
+}
+
+impl Location {
+    pub fn at(row: Row, col: Col) -> Location {
+        Location { row, col }
+    }
+
+    pub fn row(&self) -> Row {
+        self.row
+    }
+
+    pub fn col(&self) -> Col {
+        self.col
+    }
+
+    /**
+     * Location `d_row` rows down and `d_col` columns right, it may be off the board.
+     */
+    pub fn offset(&self, d_row: i32, d_col: i32) -> Location {
+        Location::at(Row(self.row.0 + d_row), Col(self.col.0 + d_col))
+    }
+
+    /**
+     * Location one step towards `direction`, moves that would leave a board of `rows`
+     * by `columns` are clamped to its edge, a diagonal move against a wall slides along it.
+     */
+    pub fn move_direction(&self, direction: Direction, rows: i32, columns: i32) -> Location {
+        let (d_row, d_col) = direction.offset();
+        Location::at(
+            Row((self.row.0 + d_row).clamp(0, (rows - 1).max(0))),
+            Col((self.col.0 + d_col).clamp(0, (columns - 1).max(0))),
+        )
+    }
+}
+
+/**
+ * Which cells are next to each other. Four only connects cells that share an edge,
+ * Eight also connects the diagonals and Hex lays the board out as hexes with six
+ * neighbors each, see `board::axial`.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Connectivity {
+    Four,
+    #[default]
+    Eight,
+    Hex,
+}
+
+impl FromStr for Connectivity {
+    type Err = anyhow::Error;
+
+    fn from_str(connectivity: &str) -> Result<Self, Self::Err> {
+        match connectivity.to_lowercase().as_str() {
+            "4" | "four" => Ok(Connectivity::Four),
+            "8" | "eight" => Ok(Connectivity::Eight),
+            "6" | "six" | "hex" => Ok(Connectivity::Hex),
+            _ => Err(anyhow!(
+                "unknown connectivity {:?}, use four, eight or hex",
+                connectivity
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Connectivity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Connectivity::Four => write!(f, "four"),
+            Connectivity::Eight => write!(f, "eight"),
+            Connectivity::Hex => write!(f, "hex"),
+        }
+    }
+}
+
+impl Connectivity {
+    /**
+     * Directions Ferris can move in, in the order neighbors are visited, top line
+     * first and left to right.
+     */
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Connectivity::Four => &[
+                Direction::Up,
+                Direction::Left,
+                Direction::Right,
+                Direction::Down,
+            ],
+            Connectivity::Eight => &[
+                Direction::UpLeft,
+                Direction::Up,
+                Direction::UpRight,
+                Direction::Left,
+                Direction::Right,
+                Direction::DownLeft,
+                Direction::Down,
+                Direction::DownRight,
+            ],
+            // Hexes have no neighbor straight up or down.
+            Connectivity::Hex => &[
+                Direction::UpLeft,
+                Direction::UpRight,
+                Direction::Left,
+                Direction::Right,
+                Direction::DownLeft,
+                Direction::DownRight,
+            ],
+        }
+    }
+
+    /**
+     * Whether Ferris can be moved in `direction`.
+     */
+    pub fn allows(&self, direction: Direction) -> bool {
+        self.directions().contains(&direction)
+    }
+
+    /**
+     * Number of moves needed to walk from one location to another on an open board.
+     */
+    pub fn steps(&self, from: &Location, to: &Location) -> i32 {
+        let d_row = (from.row.0 - to.row.0).abs();
+        let d_col = (from.col.0 - to.col.0).abs();
+        match self {
+            Connectivity::Four => d_row + d_col,
+            Connectivity::Eight => d_row.max(d_col),
+            Connectivity::Hex => {
+                let ((from_q, from_r), (to_q, to_r)) = (axial(from), axial(to));
+                let (dq, dr) = (from_q - to_q, from_r - to_r);
+                (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+            }
+        }
+    }
+
+    /**
+     * Length of a straight walk between two locations, Manhattan when moves are
+     * orthogonal and Euclidean otherwise. Neighbors are always 1 apart.
+     */
+    pub fn distance(&self, from: &Location, to: &Location) -> f32 {
+        let (dx, dy) = match self {
+            Connectivity::Hex => {
+                let ((from_x, from_y), (to_x, to_y)) = (hex_center(from), hex_center(to));
+                ((from_x - to_x) as f32, (from_y - to_y) as f32)
+            }
+            _ => (
+                (from.col.0 - to.col.0) as f32,
+                (from.row.0 - to.row.0) as f32,
+            ),
+        };
+        match self {
+            Connectivity::Four => dx.abs() + dy.abs(),
+            Connectivity::Eight | Connectivity::Hex => (dx.powi(2) + dy.powi(2)).sqrt(),
         }
-        .unwrap_or(self.clone())
+    }
+}
+
+/**
+ * Everything needed to start a new game.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameConfig {
+    pub creepers: i16,
+    pub rows: i32,
+    pub columns: i32,
+    pub creeper_strategy: CreeperStrategy,
+    pub autopilot: Autopilot,
+    pub connectivity: Connectivity,
+    // Edges of the board wrap around, see `Board::wrap`.
+    pub wrap: bool,
+    // How the walls are laid out, they are picked by the seed like the rest of the board.
+    pub maze: Maze,
+}
+
+impl GameConfig {
+    /**
+     * Fails on settings no board can be laid out with, use it on settings that come
+     * from outside the app.
+     */
+    pub fn validate(&self) -> Result<()> {
+        if self.rows < 2 || self.columns < 2 {
+            return Err(anyhow!("the board needs at least 2 rows and 2 columns"));
+        }
+        if self.creepers < 0 {
+            return Err(anyhow!("creepers can not be negative"));
+        }
+        Ok(())
     }
 }
 
 pub enum GameEvents {
-    // Initialize game
-    InitGameWithCreepers(i16, i32, i32),
+    // Initialize game, the board is picked by the seed.
+    InitGameWithCreepers(GameConfig, u64),
     // Called every few milliseconds to update the game state.
     Tick(i16),
     // Called to move ferris using the keyboard, it causes other agents to move too.
     MoveFerris(Direction),
+    // Freezes the game, ticks and key presses are ignored until it resumes.
+    Pause,
+    Resume,
+    TogglePause,
+    // Plays a single tick of a paused game.
+    Step(i16),
+    // Replaces the game with one built elsewhere, like a campaign level.
+    Load(Rc<Game>),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -74,6 +309,7 @@ pub enum Status {
     Won,
     Lost,
     Playing,
+    Paused,
     Error(String),
 }
 
@@ -84,6 +320,7 @@ impl fmt::Display for Status {
             Status::Won => write!(f, "Won"),
             Status::Lost => write!(f, "Lost"),
             Status::Playing => write!(f, "Playing"),
+            Status::Paused => write!(f, "Paused"),
             Status::Error(e) => write!(f, "Error: {}", e),
         }
     }
@@ -106,6 +343,43 @@ pub struct GameState {
     pub ferris: Ferris,
 }
 
+// Points of `Game::score`, a win is worth much more than anything else.
+const WIN_POINTS: i64 = 1000;
+// Taken away from a win for every move of Ferris, quick escapes score higher.
+const MOVE_PENALTY: i64 = 5;
+const TICK_POINTS: i64 = 2;
+// Only a win cashes in the close calls of its run.
+const CLOSE_CALL_POINTS: i64 = 20;
+// Moves the autopilot made do not earn points.
+const MANUAL_MOVE_POINTS: i64 = 10;
+
+/**
+ * Where the walls, Ferris, home and the creepers are when a game starts.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub walls: HashSet<Location>,
+    pub ferris: Location,
+    pub target: Location,
+    pub creepers: Vec<Location>,
+    // Strategy of each creeper, None and missing ones follow the strategy of the game.
+    pub creeper_strategies: Vec<Option<CreeperStrategy>>,
+}
+
+/**
+ * What happened so far in a game, the inputs of `Game::score`.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunStats {
+    // Ticks played.
+    pub ticks: u32,
+    // Moves of Ferris the player made, and the ones the autopilot made.
+    pub manual_moves: u32,
+    pub auto_moves: u32,
+    // Ticks and moves that ended with a creeper next to Ferris.
+    pub close_calls: u32,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Game {
     pub moves: Vec<GameState>,
@@ -113,6 +387,18 @@ pub struct Game {
     pub columns: i32,
     pub target: Location,
     pub status: Status,
+    pub creeper_strategy: CreeperStrategy,
+    // Creepers that do not follow `creeper_strategy`, in the order of `GameState::creepers`.
+    pub creeper_strategies: Vec<Option<CreeperStrategy>>,
+    pub autopilot: Autopilot,
+    pub connectivity: Connectivity,
+    pub wrap: bool,
+    pub maze: Maze,
+    // Cells nobody can walk on.
+    pub walls: HashSet<Location>,
+    // What the autopilot considered on the last tick, for debugging.
+    pub move_stats: Vec<MoveStats>,
+    pub stats: RunStats,
 }
 
 impl Reducible for Game {
@@ -121,308 +407,374 @@ impl Reducible for Game {
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
         // process all events.
         match action {
-            GameEvents::InitGameWithCreepers(creepers, rows, columns) => {
-                // spawn creepers
-                let mut randy = thread_rng();
-                let creepers = (0..creepers)
-                    .into_iter()
-                    .map(|_i| {
-                        let row = randy.gen_range(0..rows);
-                        let column = randy.gen_range(0..columns);
-                        Creeper {
-                            location: Location { x: row, y: column },
-                        }
-                    })
-                    .collect();
-                // TODO: validate that Ferris does not spawn next or on top of a creeper.
-                let row = randy.gen_range(0..rows);
-                let column = randy.gen_range(0..columns);
-                let ferris = Ferris {
-                    location: Location { x: row, y: column },
-                    path: vec![],
-                };
-                let row = randy.gen_range(0..rows);
-                let column = randy.gen_range(0..columns);
-                let target = Location { x: row, y: column };
-                let moves = vec![GameState { creepers, ferris }];
-                let mut game = Game {
-                    rows: rows,
-                    columns: columns,
-                    moves,
-                    target,
-                    status: Status::Idle,
-                };
-                let origin = &game.moves.last().unwrap().ferris.location;
-                let target = &game.target;
-                let result = Dijkstra::run(&game, origin, &target, &Mode::Ferris);
-
-                match result {
-                    Ok(result) => {
-                        // This unwrap will work because we just added a move.
-                        let mut ferris = &mut game.moves.last_mut().unwrap().ferris;
-                        ferris.path = result;
-                        game.validate_status();
-                        game.into()
-                    }
-                    Err(err) => {
-                        game.status = Status::Error(format!("{}", err));
-                        game.into()
-                    }
-                }
+            GameEvents::InitGameWithCreepers(config, seed) => {
+                Game::new(&config, &mut StdRng::seed_from_u64(seed)).into()
             }
             GameEvents::Tick(tick) => {
                 log!("tick {} {}", tick, self.moves.len() as u16);
-                // If not playing, then there's nothing to update.
-                if self.status != Status::Playing {
-                    return self.clone().into();
-                }
-                // Every each tick, creepers have a chance to get closer to ferris,
-                // Ferris has a chance to escape!!
-                let game = self.clone();
-                let moves = game.moves.clone();
-                let mut last_move = game.moves.last().unwrap().clone();
-                let ferris_location = &last_move.ferris.location;
-                let mut game = Game {
-                    rows: game.rows,
-                    columns: game.columns,
-                    moves,
-                    target: game.target.clone(),
-                    status: game.status.clone(),
-                };
-
-                // move creepers.
-                if tick % 2 == 0 {
-                    for mut creeper in last_move.creepers.iter_mut() {
-                        let next_position = Dijkstra::run(
-                            &game,
-                            &creeper.location,
-                            &ferris_location,
-                            &Mode::Creeper,
-                        );
-                        match next_position {
-                            Ok(next_position) => {
-                                if let Some(first) = next_position.first() {
-                                    creeper.location = first.clone();
-                                }
-                            }
-                            Err(error) => {
-                                game.status = Status::Error(error.to_string());
-                            }
-                        }
-                    }
-                }
-                game.moves.push(last_move.clone());
-                let mut mutable_game = game.clone();
-
-                // move ferris
-                let last_move = mutable_game.moves.last_mut().unwrap();
-                let path = Dijkstra::run(&game, &ferris_location, &game.target, &Mode::Ferris);
-                match path {
-                    Ok(path) => {
-                        if let Some(first) = path.first() {
-                            last_move.ferris.location = first.clone();
-                        }
-                        last_move.ferris.path = path;
-                    }
-                    Err(err) => {
-                        game.status = Status::Error(err.to_string());
-                    }
-                }
-                mutable_game.validate_status();
-                mutable_game.into()
+                self.tick(tick).into()
             }
-            GameEvents::MoveFerris(direction) => {
-                if self.status != Status::Playing && self.status != Status::Idle {
-                    return self.clone().into();
-                }
-                let game = self.clone();
-                let mut status = game.status.clone();
-                if status == Status::Idle {
-                    status = Status::Playing;
-                }
-                let mut new_moves = self.moves.clone();
-                let mut new_last_move = new_moves.last().unwrap().clone();
-                let current_ferris_position = self.moves.last().unwrap().ferris.location.clone();
-                new_last_move.ferris.location =
-                    current_ferris_position.move_direction(direction, self.rows, self.columns);
-
-                let new_path = Dijkstra::run(
-                    &game,
-                    &new_last_move.ferris.location,
-                    &game.target,
-                    &Mode::Ferris,
-                );
-
-                match new_path {
-                    Ok(new_path) => {
-                        new_last_move.ferris.path = new_path;
-                    }
-                    Err(err) => {
-                        status = Status::Error(err.to_string());
-                    }
-                }
-                if self.target == new_last_move.ferris.location {
-                    status = Status::Won;
+            GameEvents::MoveFerris(direction) => self.move_ferris(direction).into(),
+            GameEvents::Pause => self.pause().into(),
+            GameEvents::Resume => self.resume().into(),
+            GameEvents::TogglePause => {
+                if self.status == Status::Paused {
+                    self.resume().into()
+                } else {
+                    self.pause().into()
                 }
-                new_moves.push(new_last_move);
-
-                let mut game = Game {
-                    target: self.target.clone(),
-                    rows: self.rows,
-                    columns: self.columns,
-                    moves: new_moves,
-                    status,
-                };
-                game.validate_status();
-                game.into()
             }
+            GameEvents::Step(tick) => self.step(tick).into(),
+            GameEvents::Load(game) => game,
         }
     }
 }
 
 fn insert_adjacent_vertex(
-    vector: &mut Vec<VertexId>,
-    value: VertexId,
-    creepers_map: &HashMap<VertexId, bool>,
+    vector: &mut Vec<Location>,
+    location: Location,
+    creepers_map: &HashMap<Location, bool>,
     target: &Location,
     ferris_location: &Location,
 ) {
-    let (row, column) = value;
-    let location = Location::from(row, column);
-    if !creepers_map.contains_key(&value) || location == *target || location == *ferris_location {
-        vector.push(value);
+    if !creepers_map.contains_key(&location) || location == *target || location == *ferris_location
+    {
+        vector.push(location);
+    }
+}
+
+fn random_location<R: Rng>(randy: &mut R, board: &Board) -> Location {
+    let row = Row(randy.gen_range(0..board.rows));
+    let col = Col(randy.gen_range(0..board.columns));
+    Location::at(row, col)
+}
+
+/**
+ * Random cell that is not taken, the first free cell when the draws keep hitting taken
+ * cells.
+ */
+fn random_open_location<R: Rng>(
+    randy: &mut R,
+    board: &Board,
+    taken: &HashSet<Location>,
+) -> Location {
+    (0..board.len())
+        .map(|_attempt| random_location(randy, board))
+        .find(|location| !taken.contains(location))
+        .or_else(|| board.locations().find(|location| !taken.contains(location)))
+        .unwrap_or(Location::at(Row(0), Col(0)))
+}
+
+/**
+ * Resolves the moves of a group of creepers so that no two of them share a cell.
+ *
+ * `candidates[i]` lists the cells creeper `i` would like to move to, best first, and
+ * creepers are processed in `order`. A creeper can only move into a cell that is not
+ * claimed by a creeper that already moved and not held by a creeper that has yet to
+ * move, which also rules out two creepers swapping places. A creeper that can not move
+ * stays where it is.
+ */
+fn resolve_creeper_moves(
+    creepers: &[Creeper],
+    candidates: &[Vec<Location>],
+    order: &[usize],
+) -> Vec<Creeper> {
+    let mut pending: HashMap<Location, usize> = HashMap::new();
+    for creeper in creepers {
+        *pending.entry(creeper.location.clone()).or_insert(0) += 1;
+    }
+    let mut claimed: HashSet<Location> = HashSet::new();
+    let mut moved = creepers.to_vec();
+    for index in order {
+        let creeper = &creepers[*index];
+        if let Some(count) = pending.get_mut(&creeper.location) {
+            *count -= 1;
+        }
+        let next_location = candidates[*index]
+            .iter()
+            .find(|location| {
+                !claimed.contains(*location) && pending.get(*location).copied().unwrap_or(0) == 0
+            })
+            .unwrap_or(&creeper.location);
+        claimed.insert(next_location.clone());
+        moved[*index].location = next_location.clone();
     }
+    moved
 }
 
 impl Game {
+    /**
+     * Spawns creepers, Ferris and home at random locations picked by `randy`.
+     */
+    pub fn new<R: Rng>(config: &GameConfig, randy: &mut R) -> Game {
+        let GameConfig {
+            creepers,
+            rows,
+            columns,
+            connectivity,
+            wrap,
+            maze,
+            ..
+        } = config.clone();
+        let board = Board {
+            wrap,
+            ..Board::new(rows, columns, connectivity)
+        };
+        let walls = maze.walls(&board, randy);
+        // spawn creepers, they leave room for Ferris and home.
+        let mut occupied: HashSet<Location> = walls.clone();
+        let room = board.len().saturating_sub(walls.len() + 2);
+        let creepers = (0..(creepers.max(0) as usize).min(room))
+            .filter_map(|_i| {
+                // Creepers never share a cell, give up if the board is full.
+                (0..rows * columns)
+                    .map(|_attempt| random_location(randy, &board))
+                    .find(|location| occupied.insert(location.clone()))
+            })
+            .collect();
+        let ferris = random_open_location(randy, &board, &occupied);
+        occupied.insert(ferris.clone());
+        let target = random_open_location(randy, &board, &occupied);
+        Game::from_layout(
+            config,
+            Layout {
+                walls,
+                ferris,
+                target,
+                creepers,
+                creeper_strategies: vec![],
+            },
+        )
+    }
+
+    /**
+     * Starts a game on a board laid out by hand, the number of creepers and the maze of
+     * `config` are ignored in favor of `layout`.
+     */
+    pub fn from_layout(config: &GameConfig, layout: Layout) -> Game {
+        let Layout {
+            walls,
+            ferris,
+            target,
+            creepers,
+            creeper_strategies,
+        } = layout;
+        let creepers = creepers
+            .into_iter()
+            .map(|location| Creeper { location })
+            .collect();
+        let ferris = Ferris {
+            location: ferris,
+            path: vec![],
+        };
+        let moves = vec![GameState { creepers, ferris }];
+        let mut game = Game {
+            rows: config.rows,
+            columns: config.columns,
+            moves,
+            target,
+            status: Status::Idle,
+            creeper_strategy: config.creeper_strategy.clone(),
+            creeper_strategies,
+            autopilot: config.autopilot.clone(),
+            connectivity: config.connectivity,
+            wrap: config.wrap,
+            maze: config.maze,
+            walls,
+            move_stats: vec![],
+            stats: RunStats::default(),
+        };
+        let origin = game.moves.last().unwrap().ferris.location.clone();
+        let path = game.path_home(&origin);
+        // This unwrap will work because we just added a move.
+        game.moves.last_mut().unwrap().ferris.path = path;
+        game.validate_status();
+        game
+    }
+
+    /**
+     * Called every few milliseconds, creepers get closer to Ferris every other tick and
+     * Ferris follows the autopilot.
+     */
+    pub fn tick(&self, tick: i16) -> Game {
+        // If not playing, then there's nothing to update.
+        if self.status != Status::Playing {
+            return self.clone();
+        }
+        // Every each tick, creepers have a chance to get closer to ferris,
+        // Ferris has a chance to escape!!
+        let mut game = self.creepers_turn(tick);
+
+        // move ferris
+        let plan = self.autopilot.plan(&game, tick);
+        let last_move = game.moves.last_mut().unwrap();
+        let from = last_move.ferris.location.clone();
+        match plan {
+            Ok(plan) => {
+                if let Some(first) = plan.path.first() {
+                    last_move.ferris.location = first.clone();
+                }
+                last_move.ferris.path = plan.path;
+                game.move_stats = plan.move_stats;
+            }
+            Err(err) => {
+                game.status = Status::Error(err.to_string());
+            }
+        }
+        game.stats.ticks += 1;
+        game.track(&from, false);
+        game.validate_status();
+        game
+    }
+
+    /**
+     * Freezes a game that is being played. An idle game is left alone, it already
+     * waits for the first key press and resuming it must not start the autopilot.
+     */
+    pub fn pause(&self) -> Game {
+        let mut game = self.clone();
+        if game.status == Status::Playing {
+            game.status = Status::Paused;
+        }
+        game
+    }
+
+    /**
+     * Picks a paused game back up, Ferris follows the autopilot from the next tick.
+     */
+    pub fn resume(&self) -> Game {
+        let mut game = self.clone();
+        if game.status == Status::Paused {
+            game.status = Status::Playing;
+        }
+        game
+    }
+
+    /**
+     * Plays `tick` on a paused game and pauses it again, handy to watch the creepers
+     * and the autopilot one move at a time.
+     */
+    pub fn step(&self, tick: i16) -> Game {
+        if self.status != Status::Paused {
+            return self.clone();
+        }
+        let mut game = self.resume().tick(tick);
+        if game.status == Status::Playing {
+            game.status = Status::Paused;
+        }
+        game
+    }
+
+    /**
+     * Starts a new turn, creepers get closer to Ferris if it is their tick. Ferris has
+     * not moved yet and the status is not validated.
+     */
+    pub fn creepers_turn(&self, tick: i16) -> Game {
+        let mut game = self.clone();
+        let mut last_move = game.moves.last().unwrap().clone();
+
+        // move creepers.
+        if tick as i32 % TICKS_PER_CREEPER_MOVE == 0 {
+            match game.move_creepers(&last_move) {
+                Ok(creepers) => {
+                    last_move.creepers = creepers;
+                }
+                Err(error) => {
+                    game.status = Status::Error(error.to_string());
+                }
+            }
+        }
+        game.moves.push(last_move);
+        game
+    }
+
+    /**
+     * Moves Ferris one cell in `direction`, the first move starts the game.
+     */
+    pub fn move_ferris(&self, direction: Direction) -> Game {
+        if self.status != Status::Playing && self.status != Status::Idle {
+            return self.clone();
+        }
+        if !self.connectivity.allows(direction) {
+            return self.clone();
+        }
+        let mut game = self.clone();
+        if game.status == Status::Idle {
+            game.status = Status::Playing;
+        }
+        let mut new_last_move = self.moves.last().unwrap().clone();
+        new_last_move.ferris.location = self.step_ferris(&new_last_move.ferris.location, direction);
+
+        new_last_move.ferris.path = self.path_home(&new_last_move.ferris.location);
+        let from = self.moves.last().unwrap().ferris.location.clone();
+        game.moves.push(new_last_move);
+        game.track(&from, true);
+        game.validate_status();
+        game
+    }
+
+    /**
+     * Where Ferris ends up moving from `location` towards `direction`, walls stop Ferris
+     * like the edges of the board do.
+     */
+    pub fn step_ferris(&self, location: &Location, direction: Direction) -> Location {
+        let next = self.board().step(location, direction);
+        if self.walls.contains(&next) {
+            location.clone()
+        } else {
+            next
+        }
+    }
+
     pub fn get_adjacent_vertices(
         &self,
-        vertex_id: VertexId,
+        location: &Location,
         target: &Location,
         mode: &Mode,
-    ) -> Vec<VertexId> {
-        let (row, column) = vertex_id;
-        let mut vertices: Vec<VertexId> = vec![];
-        let mut creepers_map: HashMap<VertexId, bool> = HashMap::new();
+    ) -> Vec<Location> {
+        let mut vertices: Vec<Location> = vec![];
+        let mut creepers_map: HashMap<Location, bool> = HashMap::new();
         let ferris_location = self
             .moves
             .last()
             .map(|state| state.ferris.location.clone())
-            .unwrap_or(Location { x: 0, y: 0 });
+            .unwrap_or(Location::at(Row(0), Col(0)));
         if Mode::Creeper != *mode {
             if let Some(game_state) = self.moves.last() {
                 for creeper in &game_state.creepers {
                     // do not insert just the creeper current location, add +1 -1 buffer around it.
-                    let (x, y) = creeper.location.id();
-                    creepers_map.insert((x, y), true);
+                    creepers_map.insert(creeper.location.clone(), true);
                 }
             }
         }
-        // left
-        if column > 0 {
-            // up
-            if row > 0 {
-                insert_adjacent_vertex(
-                    &mut vertices,
-                    (row - 1, column - 1),
-                    &creepers_map,
-                    &target,
-                    &ferris_location,
-                );
+        for neighbor in self.board().neighbors(location) {
+            if self.walls.contains(&neighbor) {
+                continue;
             }
-            // left
             insert_adjacent_vertex(
                 &mut vertices,
-                (row, column - 1),
+                neighbor,
                 &creepers_map,
-                &target,
+                target,
                 &ferris_location,
             );
-            // bottom left
-            if row < self.rows - 1 {
-                insert_adjacent_vertex(
-                    &mut vertices,
-                    (row + 1, column - 1),
-                    &creepers_map,
-                    &target,
-                    &ferris_location,
-                );
-            }
-        }
-        // center
-        {
-            if row > 0 {
-                insert_adjacent_vertex(
-                    &mut vertices,
-                    (row - 1, column),
-                    &creepers_map,
-                    &target,
-                    &ferris_location,
-                );
-            }
-            // center bottom
-            if row < self.rows - 1 {
-                insert_adjacent_vertex(
-                    &mut vertices,
-                    (row + 1, column),
-                    &creepers_map,
-                    &target,
-                    &ferris_location,
-                );
-            }
-        }
-        // right
-        if column < self.columns - 1 {
-            // up
-            if row > 0 {
-                insert_adjacent_vertex(
-                    &mut vertices,
-                    (row - 1, column + 1),
-                    &creepers_map,
-                    &target,
-                    &ferris_location,
-                );
-            }
-            // left
-            insert_adjacent_vertex(
-                &mut vertices,
-                (row, column + 1),
-                &creepers_map,
-                &target,
-                &ferris_location,
-            );
-
-            // bottom left
-            if row < self.rows - 1 {
-                insert_adjacent_vertex(
-                    &mut vertices,
-                    (row + 1, column + 1),
-                    &creepers_map,
-                    &target,
-                    &ferris_location,
-                );
-            }
         }
         vertices
     }
 
     pub fn get_weighted_edge(
         &self,
-        _current_vertex: VertexId,
-        neighbor: VertexId,
+        _current_vertex: &Location,
+        neighbor: &Location,
         target: &Location,
         mode: &Mode,
     ) -> i32 {
-        let (row, column) = neighbor;
-        let mut cost = (((target.x - row).pow(2) as f32 + (target.y - column).pow(2) as f32).sqrt()
-            * 1000f32) as i32;
+        let board = self.board();
+        let mut cost = (board.distance(target, neighbor) * 1000f32) as i32;
         if *mode == Mode::Ferris {
             let mut shortest_distance_to_creeper = f32::MAX;
             for creeper in &self.moves.last().unwrap().creepers {
-                let creeper_location = &creeper.location;
-                let distance = ((creeper_location.x - row).pow(2) as f32
-                    + (creeper_location.y - column).pow(2) as f32)
-                    .sqrt();
+                let distance = board.distance(&creeper.location, neighbor);
                 if distance < shortest_distance_to_creeper {
                     shortest_distance_to_creeper = distance;
                 }
@@ -435,6 +787,211 @@ impl Game {
         cost
     }
 
+    /**
+     * Moves every creeper one step towards the goal picked by the game's creeper
+     * strategy, resolving the moves as a group so that no two creepers end up on the
+     * same cell.
+     *
+     * Creepers closer to Ferris move first (ties are broken by their index), that way
+     * a line of creepers chasing Ferris can advance together.
+     */
+    /**
+     * Strategy the creeper at `index` of `GameState::creepers` follows.
+     */
+    pub fn strategy_of(&self, index: usize) -> &CreeperStrategy {
+        self.creeper_strategies
+            .get(index)
+            .and_then(Option::as_ref)
+            .unwrap_or(&self.creeper_strategy)
+    }
+
+    pub fn move_creepers(&self, state: &GameState) -> Result<Vec<Creeper>> {
+        let distance = |location: &Location, goal: &Location| {
+            self.get_weighted_edge(location, location, goal, &Mode::Creeper)
+        };
+        let ferris_location = &state.ferris.location;
+        let mut order: Vec<usize> = (0..state.creepers.len()).collect();
+        order.sort_by_key(|index| {
+            (
+                distance(&state.creepers[*index].location, ferris_location),
+                *index,
+            )
+        });
+        let strategies: Vec<CreeperStrategy> = (0..state.creepers.len())
+            .map(|index| self.strategy_of(index).clone())
+            .collect();
+        let goals = assign_goals(state, &self.target, &strategies, &self.board(), &order);
+
+        let mut candidates = vec![];
+        for (creeper, goal) in state.creepers.iter().zip(goals.iter()) {
+            let mut creeper_candidates = vec![];
+            // A creeper walled off from its goal only takes the neighbors below.
+            let path =
+                Dijkstra::run(self, &creeper.location, goal, &Mode::Creeper).unwrap_or_default();
+            if let Some(first) = path.first() {
+                creeper_candidates.push(first.clone());
+            }
+            // If the shortest path is blocked, any other neighbor that gets the creeper
+            // closer to its goal will do.
+            let current_distance = distance(&creeper.location, goal);
+            let mut neighbors: Vec<Location> = self
+                .get_adjacent_vertices(&creeper.location, goal, &Mode::Creeper)
+                .into_iter()
+                .filter(|location| distance(location, goal) < current_distance)
+                .collect();
+            neighbors.sort_by_key(|location| distance(location, goal));
+            creeper_candidates.extend(neighbors);
+            candidates.push(creeper_candidates);
+        }
+        Ok(resolve_creeper_moves(&state.creepers, &candidates, &order))
+    }
+
+    /**
+     * Counts the move of Ferris from `from` to where it is now, `manual` when the player
+     * made it, and how close the creepers got.
+     */
+    fn track(&mut self, from: &Location, manual: bool) {
+        let board = self.board();
+        let state = match self.moves.last() {
+            Some(state) => state,
+            None => return,
+        };
+        if state.ferris.location != *from {
+            if manual {
+                self.stats.manual_moves += 1;
+            } else {
+                self.stats.auto_moves += 1;
+            }
+        }
+        let close_call = state
+            .creepers
+            .iter()
+            .any(|creeper| board.steps(&creeper.location, &state.ferris.location) <= 1);
+        if close_call {
+            self.stats.close_calls += 1;
+        }
+    }
+
+    /**
+     * Points of the game so far, by what earned them. Wins score much more, and the
+     * fewer moves Ferris takes the better. Every tick survived and every move the player
+     * made rather than the autopilot earn points, close calls with a creeper only pay
+     * off when Ferris makes it home.
+     */
+    pub fn score_parts(&self) -> Vec<(&'static str, i64)> {
+        let won = self.status == Status::Won;
+        vec![
+            ("Home", if won { WIN_POINTS } else { 0 }),
+            (
+                "Moves",
+                if won {
+                    -MOVE_PENALTY * self.ferris_moves() as i64
+                } else {
+                    0
+                },
+            ),
+            ("Survival", TICK_POINTS * self.stats.ticks as i64),
+            (
+                "Close calls",
+                if won {
+                    CLOSE_CALL_POINTS * self.stats.close_calls as i64
+                } else {
+                    0
+                },
+            ),
+            (
+                "Manual moves",
+                MANUAL_MOVE_POINTS * self.stats.manual_moves as i64,
+            ),
+        ]
+    }
+
+    /**
+     * Sum of `score_parts`, never below zero.
+     */
+    pub fn score(&self) -> u32 {
+        let total: i64 = self
+            .score_parts()
+            .iter()
+            .map(|(_name, points)| points)
+            .sum();
+        total.clamp(0, u32::MAX as i64) as u32
+    }
+
+    /**
+     * Number of times Ferris changed cells since the game started.
+     */
+    pub fn ferris_moves(&self) -> usize {
+        self.moves
+            .windows(2)
+            .filter(|pair| pair[0].ferris.location != pair[1].ferris.location)
+            .count()
+    }
+
+    /**
+     * Shortest path from `from` to home that keeps away from the creepers. When creepers
+     * block every way home, like one standing in a corridor of a maze, the path goes
+     * past them instead, and it is empty when walls cut `from` off from home.
+     */
+    pub fn path_home(&self, from: &Location) -> Vec<Location> {
+        Dijkstra::run(self, from, &self.target, &Mode::Ferris)
+            .or_else(|_| Dijkstra::run(self, from, &self.target, &Mode::Creeper))
+            .unwrap_or_default()
+    }
+
+    /**
+     * Number of moves from every cell of the board to `target`, ignoring creepers.
+     */
+    pub fn steps_to(&self, target: &Location) -> HashMap<Location, i32> {
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        distances.insert(target.clone(), 0);
+        queue.push_back(target.clone());
+        while let Some(location) = queue.pop_front() {
+            let distance = distances[&location];
+            for neighbor in self.get_adjacent_vertices(&location, target, &Mode::Creeper) {
+                if let Entry::Vacant(entry) = distances.entry(neighbor.clone()) {
+                    entry.insert(distance + 1);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        distances
+    }
+
+    /**
+     * Size, connectivity and edges of the board the game is played on.
+     */
+    pub fn board(&self) -> Board {
+        Board {
+            wrap: self.wrap,
+            ..Board::new(self.rows, self.columns, self.connectivity)
+        }
+    }
+
+    /**
+     * Copy of the game that only remembers `state`, handy to play moves out without
+     * carrying the whole history around.
+     */
+    pub fn with_state(&self, state: GameState) -> Game {
+        Game {
+            moves: vec![state],
+            rows: self.rows,
+            columns: self.columns,
+            target: self.target.clone(),
+            status: self.status.clone(),
+            creeper_strategy: self.creeper_strategy.clone(),
+            creeper_strategies: self.creeper_strategies.clone(),
+            autopilot: self.autopilot.clone(),
+            connectivity: self.connectivity,
+            wrap: self.wrap,
+            maze: self.maze,
+            walls: self.walls.clone(),
+            move_stats: vec![],
+            stats: self.stats.clone(),
+        }
+    }
+
     pub fn validate_status(&mut self) {
         // If creeper hit ferris, user lost.
         if let Some(state) = self.moves.last() {
@@ -452,85 +1009,683 @@ impl Game {
     }
 }
 
+#[cfg(test)]
+impl Game {
+    /**
+     * Idle game on an open board with nothing on it, tests fill in what they need with
+     * `..Game::empty(rows, columns)`.
+     */
+    pub(crate) fn empty(rows: i32, columns: i32) -> Game {
+        Game {
+            moves: vec![],
+            rows,
+            columns,
+            target: Location::at(Row(0), Col(0)),
+            status: Status::Idle,
+            creeper_strategy: CreeperStrategy::Chase,
+            creeper_strategies: vec![],
+            autopilot: Autopilot::Greedy,
+            connectivity: Connectivity::Eight,
+            wrap: false,
+            maze: Maze::Open,
+            walls: HashSet::new(),
+            move_stats: vec![],
+            stats: RunStats::default(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
+        autopilot::Autopilot,
+        board::{Col, Row},
         dijkstra::Mode,
-        model::{Creeper, GameState, Status},
+        hunt::CreeperStrategy,
+        level::Level,
+        maze::Maze,
+        model::{Connectivity, Creeper, Direction, GameState, RunStats, Status},
     };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
 
-    use super::{Game, Location};
+    use super::{resolve_creeper_moves, Game, GameConfig, Location};
 
     #[test]
     fn get_adjacent_vertices_happy_path() {
-        let game = Game {
-            moves: vec![],
-            rows: 10,
-            columns: 10,
-            target: Location { x: 0, y: 0 },
-            status: Status::Idle,
-        };
-        let adjacent_vertices = game.get_adjacent_vertices((5, 5), &game.target, &Mode::Ferris);
+        let game = Game::empty(10, 10);
+        let adjacent_vertices =
+            game.get_adjacent_vertices(&Location::at(Row(5), Col(5)), &game.target, &Mode::Ferris);
         let expected_vertices = vec![
-            (4, 4),
-            (5, 4),
-            (6, 4),
-            (4, 5),
-            (6, 5),
-            (4, 6),
-            (5, 6),
-            (6, 6),
+            Location::at(Row(4), Col(4)),
+            Location::at(Row(4), Col(5)),
+            Location::at(Row(4), Col(6)),
+            Location::at(Row(5), Col(4)),
+            Location::at(Row(5), Col(6)),
+            Location::at(Row(6), Col(4)),
+            Location::at(Row(6), Col(5)),
+            Location::at(Row(6), Col(6)),
         ];
         assert_eq!(adjacent_vertices, expected_vertices);
     }
 
     #[test]
     fn get_adjacent_vertices_top_left() {
-        let game = Game {
-            moves: vec![],
-            rows: 10,
-            columns: 10,
-            target: Location { x: 0, y: 0 },
-            status: Status::Idle,
-        };
-        let adjacent_vertices = game.get_adjacent_vertices((0, 0), &game.target, &Mode::Ferris);
-        let expected_vertices = vec![(1, 0), (0, 1), (1, 1)];
+        let game = Game::empty(10, 10);
+        let adjacent_vertices =
+            game.get_adjacent_vertices(&Location::at(Row(0), Col(0)), &game.target, &Mode::Ferris);
+        let expected_vertices = vec![
+            Location::at(Row(0), Col(1)),
+            Location::at(Row(1), Col(0)),
+            Location::at(Row(1), Col(1)),
+        ];
         assert_eq!(adjacent_vertices, expected_vertices);
     }
 
     #[test]
     fn get_adjacent_vertices_bottom_right() {
+        let game = Game::empty(10, 10);
+        let adjacent_vertices =
+            game.get_adjacent_vertices(&Location::at(Row(9), Col(9)), &game.target, &Mode::Ferris);
+        let expected_vertices = vec![
+            Location::at(Row(8), Col(8)),
+            Location::at(Row(8), Col(9)),
+            Location::at(Row(9), Col(8)),
+        ];
+        assert_eq!(adjacent_vertices, expected_vertices);
+    }
+
+    #[test]
+    fn get_adjacent_vertices_with_creepers() {
         let game = Game {
-            moves: vec![],
-            rows: 10,
-            columns: 10,
-            target: Location { x: 0, y: 0 },
-            status: Status::Idle,
+            moves: vec![GameState {
+                creepers: vec![Creeper {
+                    location: Location::at(Row(4), Col(5)),
+                }],
+                ferris: crate::model::Ferris {
+                    location: Location::at(Row(1), Col(1)),
+                    path: vec![],
+                },
+            }],
+            ..Game::empty(10, 10)
         };
-        let adjacent_vertices = game.get_adjacent_vertices((9, 9), &game.target, &Mode::Ferris);
-        let expected_vertices = vec![(8, 8), (9, 8), (8, 9)];
+        let adjacent_vertices =
+            game.get_adjacent_vertices(&Location::at(Row(5), Col(5)), &game.target, &Mode::Ferris);
+        let expected_vertices = vec![
+            Location::at(Row(4), Col(4)),
+            Location::at(Row(4), Col(6)),
+            Location::at(Row(5), Col(4)),
+            Location::at(Row(5), Col(6)),
+            Location::at(Row(6), Col(4)),
+            Location::at(Row(6), Col(5)),
+            Location::at(Row(6), Col(6)),
+        ];
         assert_eq!(adjacent_vertices, expected_vertices);
     }
 
     #[test]
-    fn get_adjacent_vertices_with_creepers() {
+    fn resolve_creeper_moves_head_on_swap() {
+        let creepers = vec![
+            Creeper {
+                location: Location::at(Row(2), Col(2)),
+            },
+            Creeper {
+                location: Location::at(Row(3), Col(2)),
+            },
+        ];
+        let candidates = vec![
+            vec![Location::at(Row(3), Col(2))],
+            vec![Location::at(Row(2), Col(2))],
+        ];
+        let moved = resolve_creeper_moves(&creepers, &candidates, &[0, 1]);
+        assert_eq!(moved, creepers);
+        let moved = resolve_creeper_moves(&creepers, &candidates, &[1, 0]);
+        assert_eq!(moved, creepers);
+    }
+
+    #[test]
+    fn resolve_creeper_moves_head_on_swap_with_fallback() {
+        let creepers = vec![
+            Creeper {
+                location: Location::at(Row(2), Col(2)),
+            },
+            Creeper {
+                location: Location::at(Row(3), Col(2)),
+            },
+        ];
+        let candidates = vec![
+            vec![Location::at(Row(3), Col(2)), Location::at(Row(3), Col(3))],
+            vec![Location::at(Row(2), Col(2))],
+        ];
+        let moved = resolve_creeper_moves(&creepers, &candidates, &[0, 1]);
+        let expected = vec![
+            Creeper {
+                location: Location::at(Row(3), Col(3)),
+            },
+            Creeper {
+                location: Location::at(Row(2), Col(2)),
+            },
+        ];
+        assert_eq!(moved, expected);
+    }
+
+    #[test]
+    fn resolve_creeper_moves_follow_the_leader() {
+        let creepers = vec![
+            Creeper {
+                location: Location::at(Row(2), Col(2)),
+            },
+            Creeper {
+                location: Location::at(Row(3), Col(2)),
+            },
+        ];
+        let candidates = vec![
+            vec![Location::at(Row(3), Col(2))],
+            vec![Location::at(Row(4), Col(2))],
+        ];
+        let moved = resolve_creeper_moves(&creepers, &candidates, &[1, 0]);
+        let expected = vec![
+            Creeper {
+                location: Location::at(Row(3), Col(2)),
+            },
+            Creeper {
+                location: Location::at(Row(4), Col(2)),
+            },
+        ];
+        assert_eq!(moved, expected);
+    }
+
+    #[test]
+    fn move_creepers_do_not_stack() {
+        let game = Game {
+            moves: vec![GameState {
+                creepers: vec![
+                    Creeper {
+                        location: Location::at(Row(2), Col(4)),
+                    },
+                    Creeper {
+                        location: Location::at(Row(2), Col(6)),
+                    },
+                ],
+                ferris: crate::model::Ferris {
+                    location: Location::at(Row(8), Col(5)),
+                    path: vec![],
+                },
+            }],
+            status: Status::Playing,
+            ..Game::empty(10, 10)
+        };
+        let moved = game.move_creepers(game.moves.last().unwrap()).unwrap();
+        let expected = vec![
+            Creeper {
+                location: Location::at(Row(3), Col(5)),
+            },
+            Creeper {
+                location: Location::at(Row(3), Col(6)),
+            },
+        ];
+        assert_eq!(moved, expected);
+    }
+
+    #[test]
+    fn move_creepers_only_one_catches_ferris() {
+        let game = Game {
+            moves: vec![GameState {
+                creepers: vec![
+                    Creeper {
+                        location: Location::at(Row(4), Col(5)),
+                    },
+                    Creeper {
+                        location: Location::at(Row(6), Col(5)),
+                    },
+                ],
+                ferris: crate::model::Ferris {
+                    location: Location::at(Row(5), Col(5)),
+                    path: vec![],
+                },
+            }],
+            status: Status::Playing,
+            ..Game::empty(10, 10)
+        };
+        let moved = game.move_creepers(game.moves.last().unwrap()).unwrap();
+        let expected = vec![
+            Creeper {
+                location: Location::at(Row(5), Col(5)),
+            },
+            Creeper {
+                location: Location::at(Row(6), Col(5)),
+            },
+        ];
+        assert_eq!(moved, expected);
+    }
+
+    #[test]
+    fn move_creepers_pack_cuts_off_path_home() {
+        let game = Game {
+            moves: vec![GameState {
+                creepers: vec![
+                    Creeper {
+                        location: Location::at(Row(0), Col(2)),
+                    },
+                    Creeper {
+                        location: Location::at(Row(4), Col(3)),
+                    },
+                ],
+                ferris: crate::model::Ferris {
+                    location: Location::at(Row(0), Col(0)),
+                    path: vec![
+                        Location::at(Row(1), Col(0)),
+                        Location::at(Row(2), Col(0)),
+                        Location::at(Row(3), Col(0)),
+                        Location::at(Row(4), Col(0)),
+                        Location::at(Row(5), Col(0)),
+                        Location::at(Row(6), Col(0)),
+                    ],
+                },
+            }],
+            target: Location::at(Row(6), Col(0)),
+            status: Status::Playing,
+            creeper_strategy: CreeperStrategy::Pack,
+            ..Game::empty(10, 10)
+        };
+        let moved = game.move_creepers(game.moves.last().unwrap()).unwrap();
+        let expected = vec![
+            Creeper {
+                location: Location::at(Row(0), Col(1)),
+            },
+            // Heads for (0, 6) instead of chasing Ferris.
+            Creeper {
+                location: Location::at(Row(5), Col(2)),
+            },
+        ];
+        assert_eq!(moved, expected);
+    }
+
+    #[test]
+    fn paused_game_only_moves_on_step() {
         let game = Game {
             moves: vec![GameState {
                 creepers: vec![Creeper {
-                    location: Location { x: 5, y: 4 },
+                    location: Location::at(Row(9), Col(9)),
                 }],
                 ferris: crate::model::Ferris {
-                    location: Location { x: 1, y: 1 },
+                    location: Location::at(Row(0), Col(0)),
                     path: vec![],
                 },
             }],
-            rows: 10,
-            columns: 10,
-            target: Location { x: 0, y: 0 },
+            target: Location::at(Row(5), Col(0)),
+            status: Status::Playing,
+            ..Game::empty(10, 10)
+        };
+        let paused = game.pause();
+        assert_eq!(paused.status, Status::Paused);
+        assert_eq!(paused.tick(2), paused);
+        assert_eq!(paused.move_ferris(super::Direction::Down), paused);
+
+        let stepped = paused.step(2);
+        assert_eq!(stepped.status, Status::Paused);
+        assert_eq!(stepped.moves.len(), 2);
+        assert_eq!(
+            stepped.moves.last().unwrap().ferris.location,
+            Location::at(Row(1), Col(0))
+        );
+        assert_eq!(stepped.resume().status, Status::Playing);
+
+        let idle = Game {
             status: Status::Idle,
+            ..game
         };
-        let adjacent_vertices = game.get_adjacent_vertices((5, 5), &game.target, &Mode::Ferris);
-        let expected_vertices = vec![(4, 4), (6, 4), (4, 5), (6, 5), (4, 6), (5, 6), (6, 6)];
-        assert_eq!(adjacent_vertices, expected_vertices);
+        assert_eq!(idle.pause(), idle);
+        assert_eq!(idle.pause().step(2), idle);
+    }
+
+    #[test]
+    fn init_with_the_same_seed_replays_the_board() {
+        use super::{GameConfig, GameEvents};
+        use std::rc::Rc;
+        use yew::Reducible;
+
+        let config = GameConfig {
+            creepers: 4,
+            rows: 8,
+            columns: 10,
+            creeper_strategy: CreeperStrategy::Chase,
+            autopilot: Autopilot::Greedy,
+            connectivity: Connectivity::Eight,
+            wrap: false,
+            maze: Maze::Open,
+        };
+        let game = Rc::new(Game::new(&config, &mut rand::thread_rng()));
+        let first = game
+            .clone()
+            .reduce(GameEvents::InitGameWithCreepers(config.clone(), 42));
+        let again = game
+            .clone()
+            .reduce(GameEvents::InitGameWithCreepers(config.clone(), 42));
+        assert_eq!(first, again);
+        let other = game.reduce(GameEvents::InitGameWithCreepers(config, 43));
+        assert_ne!(first.moves, other.moves);
+    }
+
+    #[test]
+    fn mazes_leave_a_way_home() {
+        for maze in [Maze::Backtracker, Maze::Prim, Maze::Caves] {
+            for seed in 0..5 {
+                let config = GameConfig {
+                    creepers: 4,
+                    rows: 12,
+                    columns: 24,
+                    creeper_strategy: CreeperStrategy::Chase,
+                    autopilot: Autopilot::Greedy,
+                    connectivity: Connectivity::Four,
+                    wrap: false,
+                    maze,
+                };
+                let game = Game::new(&config, &mut StdRng::seed_from_u64(seed));
+                assert!(!game.walls.is_empty());
+                let state = game.moves.last().unwrap();
+                let ferris = &state.ferris.location;
+                assert!(!game.walls.contains(ferris));
+                assert!(!game.walls.contains(&game.target));
+                for creeper in &state.creepers {
+                    assert!(!game.walls.contains(&creeper.location));
+                }
+                assert_eq!(state.ferris.path.last(), Some(&game.target));
+                for location in &state.ferris.path {
+                    assert!(!game.walls.contains(location));
+                }
+                // Walls stop Ferris.
+                for direction in Connectivity::Four.directions() {
+                    let moved = game.step_ferris(ferris, *direction);
+                    assert!(!game.walls.contains(&moved));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn spawns_never_overlap() {
+        for seed in 0..20 {
+            let config = GameConfig {
+                creepers: 20,
+                rows: 3,
+                columns: 3,
+                creeper_strategy: CreeperStrategy::Chase,
+                autopilot: Autopilot::Greedy,
+                connectivity: Connectivity::Eight,
+                wrap: false,
+                maze: Maze::Open,
+            };
+            let game = Game::new(&config, &mut StdRng::seed_from_u64(seed));
+            let state = game.moves.last().unwrap();
+            let mut cells: HashSet<&Location> = state
+                .creepers
+                .iter()
+                .map(|creeper| &creeper.location)
+                .collect();
+            assert!(cells.insert(&state.ferris.location));
+            assert!(cells.insert(&game.target));
+            assert_eq!(cells.len(), state.creepers.len() + 2);
+        }
+    }
+
+    #[test]
+    fn creepers_in_corridors_do_not_let_ferris_jump_walls() {
+        for maze in [Maze::Backtracker, Maze::Prim] {
+            for seed in 0..20 {
+                let config = GameConfig {
+                    creepers: 4,
+                    rows: 15,
+                    columns: 15,
+                    creeper_strategy: CreeperStrategy::Chase,
+                    autopilot: Autopilot::Greedy,
+                    connectivity: Connectivity::Four,
+                    wrap: false,
+                    maze,
+                };
+                let mut game = Game::new(&config, &mut StdRng::seed_from_u64(seed));
+                game.status = Status::Playing;
+                for tick in 1..200 {
+                    let from = game.moves.last().unwrap().ferris.location.clone();
+                    game = game.tick(tick);
+                    let to = &game.moves.last().unwrap().ferris.location;
+                    assert!(Connectivity::Four.steps(&from, to) <= 1);
+                    assert!(!game.walls.contains(to));
+                    if game.status != Status::Playing {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn direction_from_swipe() {
+        use super::Direction;
+
+        assert_eq!(Direction::from_swipe(80.0, 10.0), Some(Direction::Right));
+        assert_eq!(Direction::from_swipe(-80.0, 40.0), Some(Direction::Left));
+        assert_eq!(Direction::from_swipe(5.0, -50.0), Some(Direction::Up));
+        assert_eq!(Direction::from_swipe(-20.0, 60.0), Some(Direction::Down));
+        assert_eq!(Direction::from_swipe(10.0, -12.0), None);
+    }
+
+    #[test]
+    fn move_direction_clamps_to_the_board() {
+        use super::Direction;
+
+        let center = Location::at(Row(2), Col(2));
+        assert_eq!(
+            center.move_direction(Direction::UpLeft, 4, 5),
+            Location::at(Row(1), Col(1))
+        );
+        assert_eq!(
+            center.move_direction(Direction::DownRight, 4, 5),
+            Location::at(Row(3), Col(3))
+        );
+        let top_left = Location::at(Row(0), Col(0));
+        assert_eq!(top_left.move_direction(Direction::Up, 4, 5), top_left);
+        assert_eq!(top_left.move_direction(Direction::UpLeft, 4, 5), top_left);
+        // Diagonal moves against a wall slide along it.
+        assert_eq!(
+            top_left.move_direction(Direction::UpRight, 4, 5),
+            Location::at(Row(0), Col(1))
+        );
+        assert_eq!(
+            top_left.move_direction(Direction::DownLeft, 4, 5),
+            Location::at(Row(1), Col(0))
+        );
+        let bottom_right = Location::at(Row(3), Col(4));
+        assert_eq!(
+            bottom_right.move_direction(Direction::DownRight, 4, 5),
+            bottom_right
+        );
+        assert_eq!(
+            bottom_right.move_direction(Direction::DownLeft, 4, 5),
+            Location::at(Row(3), Col(3))
+        );
+        assert_eq!(
+            bottom_right.move_direction(Direction::UpRight, 4, 5),
+            Location::at(Row(2), Col(4))
+        );
+        assert_eq!(
+            "down_left".parse::<Direction>().unwrap(),
+            Direction::DownLeft
+        );
+    }
+
+    #[test]
+    fn four_connected_board() {
+        use super::{Connectivity, Direction};
+
+        let game = Game {
+            moves: vec![GameState {
+                creepers: vec![],
+                ferris: crate::model::Ferris {
+                    location: Location::at(Row(5), Col(5)),
+                    path: vec![],
+                },
+            }],
+            status: Status::Playing,
+            connectivity: Connectivity::Four,
+            ..Game::empty(10, 10)
+        };
+        let adjacent_vertices =
+            game.get_adjacent_vertices(&Location::at(Row(5), Col(5)), &game.target, &Mode::Ferris);
+        assert_eq!(
+            adjacent_vertices,
+            vec![
+                Location::at(Row(4), Col(5)),
+                Location::at(Row(5), Col(4)),
+                Location::at(Row(5), Col(6)),
+                Location::at(Row(6), Col(5))
+            ]
+        );
+        let adjacent_vertices =
+            game.get_adjacent_vertices(&Location::at(Row(0), Col(0)), &game.target, &Mode::Creeper);
+        assert_eq!(
+            adjacent_vertices,
+            vec![Location::at(Row(0), Col(1)), Location::at(Row(1), Col(0))]
+        );
+
+        // Manhattan distance, one step right and one down cost the same as two down.
+        assert_eq!(
+            game.get_weighted_edge(
+                &Location::at(Row(0), Col(0)),
+                &Location::at(Row(1), Col(1)),
+                &game.target,
+                &Mode::Creeper
+            ),
+            game.get_weighted_edge(
+                &Location::at(Row(0), Col(0)),
+                &Location::at(Row(2), Col(0)),
+                &game.target,
+                &Mode::Creeper
+            )
+        );
+        assert_eq!(
+            Connectivity::Four.steps(&Location::at(Row(0), Col(0)), &Location::at(Row(2), Col(3))),
+            5
+        );
+        assert_eq!(
+            Connectivity::Eight.steps(&Location::at(Row(0), Col(0)), &Location::at(Row(2), Col(3))),
+            3
+        );
+
+        // Diagonal key presses are ignored.
+        assert_eq!(game.move_ferris(Direction::UpLeft), game);
+        let moved = game.move_ferris(Direction::Up);
+        assert_eq!(
+            moved.moves.last().unwrap().ferris.location,
+            Location::at(Row(4), Col(5))
+        );
+        let path = &moved.moves.last().unwrap().ferris.path;
+        for (from, to) in path.iter().zip(path.iter().skip(1)) {
+            assert_eq!(Connectivity::Four.steps(from, to), 1);
+        }
+    }
+
+    #[test]
+    fn hex_board() {
+        use super::{Connectivity, Direction};
+
+        let game = Game {
+            moves: vec![GameState {
+                creepers: vec![],
+                ferris: crate::model::Ferris {
+                    location: Location::at(Row(5), Col(5)),
+                    path: vec![],
+                },
+            }],
+            status: Status::Playing,
+            connectivity: Connectivity::Hex,
+            ..Game::empty(10, 10)
+        };
+        let adjacent_vertices =
+            game.get_adjacent_vertices(&Location::at(Row(5), Col(5)), &game.target, &Mode::Ferris);
+        assert_eq!(
+            adjacent_vertices,
+            vec![
+                Location::at(Row(4), Col(5)),
+                Location::at(Row(4), Col(6)),
+                Location::at(Row(5), Col(4)),
+                Location::at(Row(5), Col(6)),
+                Location::at(Row(6), Col(5)),
+                Location::at(Row(6), Col(6))
+            ]
+        );
+        assert_eq!(
+            Connectivity::Hex.steps(&Location::at(Row(5), Col(5)), &Location::at(Row(0), Col(0))),
+            8
+        );
+
+        // Hexes have no neighbor straight up.
+        assert_eq!(game.move_ferris(Direction::Up), game);
+        let moved = game.move_ferris(Direction::UpRight);
+        assert_eq!(
+            moved.moves.last().unwrap().ferris.location,
+            Location::at(Row(4), Col(6))
+        );
+        let path = &moved.moves.last().unwrap().ferris.path;
+        assert_eq!(path.last(), Some(&game.target));
+        assert_eq!(
+            path.len() as i32,
+            Connectivity::Hex.steps(&Location::at(Row(4), Col(6)), &game.target)
+        );
+        for (from, to) in path.iter().zip(path.iter().skip(1)) {
+            assert_eq!(Connectivity::Hex.steps(from, to), 1);
+        }
+    }
+
+    fn level_game(map: &[&str]) -> Game {
+        Level {
+            map: map.iter().map(|line| line.to_string()).collect(),
+            ..Level::default()
+        }
+        .game(&Autopilot::Greedy)
+        .unwrap()
+    }
+
+    #[test]
+    fn scores_add_up_the_run() {
+        let play = || {
+            let mut game = level_game(&["F...H", "....."]);
+            for _step in 0..3 {
+                game = game.move_ferris(Direction::Right);
+            }
+            game.tick(1)
+        };
+        let game = play();
+        assert_eq!(game.status, Status::Won);
+        assert_eq!(
+            game.stats,
+            RunStats {
+                ticks: 1,
+                manual_moves: 3,
+                auto_moves: 1,
+                close_calls: 0,
+            }
+        );
+        // A win in 4 moves, a tick and 3 manual moves.
+        assert_eq!(game.score(), 1000 - 5 * 4 + 2 + 3 * 10);
+        assert_eq!(play().score(), game.score());
+
+        let game = level_game(&["F....", "..C.H"]).move_ferris(Direction::Right);
+        assert_eq!(game.stats.close_calls, 1);
+        assert_eq!(game.score(), 10);
+        // Close calls only count for a win.
+        let won = Game {
+            status: Status::Won,
+            ..game.clone()
+        };
+        assert_eq!(won.score(), 1000 - 5 + 20 + 10);
+        let lost = Game {
+            status: Status::Lost,
+            ..game
+        };
+        assert_eq!(lost.score(), 10);
+        // Bumping into the edge is not a move.
+        let game = level_game(&["F....", "....H"]).move_ferris(Direction::Up);
+        assert_eq!((game.stats.manual_moves, game.score()), (0, 0));
     }
 }
@@ -0,0 +1,305 @@
+// The pyo3 macros convert PyResult errors into themselves, constructors take every
+// setting as a keyword argument.
+#![allow(clippy::useless_conversion, clippy::too_many_arguments)]
+
+use crate::env::{Action, Env, EnvConfig, Observation, RewardConfig};
+use crate::model::{Game, GameConfig, Location, Status};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rand::{rngs::StdRng, SeedableRng};
+
+fn value_error(err: anyhow::Error) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn game_config(
+    rows: i32,
+    columns: i32,
+    creepers: i16,
+    creeper_strategy: &str,
+    autopilot: &str,
+    connectivity: &str,
+    wrap: bool,
+    maze: &str,
+) -> PyResult<GameConfig> {
+    let config = GameConfig {
+        creepers,
+        rows,
+        columns,
+        creeper_strategy: creeper_strategy.parse().map_err(value_error)?,
+        autopilot: autopilot.parse().map_err(value_error)?,
+        connectivity: connectivity.parse().map_err(value_error)?,
+        wrap,
+        maze: maze.parse().map_err(value_error)?,
+    };
+    config.validate().map_err(value_error)?;
+    Ok(config)
+}
+
+fn cell(location: &Location) -> (i32, i32) {
+    (location.row().0, location.col().0)
+}
+
+fn status_name(status: &Status) -> String {
+    status.to_string().to_lowercase()
+}
+
+/**
+ * The game model, Ferris waits in `idle` until it is moved or `start` is called.
+ */
+#[pyclass(name = "Game")]
+pub struct PyGame {
+    game: Game,
+    tick: i16,
+}
+
+#[pymethods]
+impl PyGame {
+    #[new]
+    #[pyo3(signature = (rows=12, columns=24, creepers=10, seed=None, creeper_strategy="chase", autopilot="greedy", connectivity="eight", wrap=false, maze="open"))]
+    fn new(
+        rows: i32,
+        columns: i32,
+        creepers: i16,
+        seed: Option<u64>,
+        creeper_strategy: &str,
+        autopilot: &str,
+        connectivity: &str,
+        wrap: bool,
+        maze: &str,
+    ) -> PyResult<PyGame> {
+        let config = game_config(
+            rows,
+            columns,
+            creepers,
+            creeper_strategy,
+            autopilot,
+            connectivity,
+            wrap,
+            maze,
+        )?;
+        let mut randy = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Ok(PyGame {
+            game: Game::new(&config, &mut randy),
+            tick: 0,
+        })
+    }
+
+    /**
+     * Lets the autopilot drive Ferris on the next ticks.
+     */
+    fn start(&mut self) {
+        if self.game.status == Status::Idle {
+            self.game.status = Status::Playing;
+        }
+    }
+
+    /**
+     * Moves Ferris one cell, `direction` is any name `Direction` reads, like "up" or
+     * "down_left". Moves the board does not allow leave Ferris where it is.
+     */
+    fn move_ferris(&mut self, direction: &str) -> PyResult<String> {
+        self.game = self
+            .game
+            .move_ferris(direction.parse().map_err(value_error)?);
+        Ok(self.status())
+    }
+
+    /**
+     * Advances the game one tick, returns the status afterwards.
+     */
+    fn tick(&mut self) -> String {
+        self.tick = self.tick.wrapping_add(1);
+        self.game = self.game.tick(self.tick);
+        self.status()
+    }
+
+    #[getter]
+    fn status(&self) -> String {
+        status_name(&self.game.status)
+    }
+
+    #[getter]
+    fn ticks(&self) -> i16 {
+        self.tick
+    }
+
+    #[getter]
+    fn score(&self) -> u32 {
+        self.game.score()
+    }
+
+    #[getter]
+    fn rows(&self) -> i32 {
+        self.game.rows
+    }
+
+    #[getter]
+    fn columns(&self) -> i32 {
+        self.game.columns
+    }
+
+    #[getter]
+    fn target(&self) -> (i32, i32) {
+        cell(&self.game.target)
+    }
+
+    #[getter]
+    fn ferris(&self) -> Option<(i32, i32)> {
+        self.game
+            .moves
+            .last()
+            .map(|state| cell(&state.ferris.location))
+    }
+
+    #[getter]
+    fn creepers(&self) -> Vec<(i32, i32)> {
+        self.game
+            .moves
+            .last()
+            .map(|state| {
+                state
+                    .creepers
+                    .iter()
+                    .map(|creeper| cell(&creeper.location))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[getter]
+    fn path(&self) -> Vec<(i32, i32)> {
+        self.game
+            .moves
+            .last()
+            .map(|state| state.ferris.path.iter().map(cell).collect())
+            .unwrap_or_default()
+    }
+
+    #[getter]
+    fn walls(&self) -> Vec<(i32, i32)> {
+        let mut walls: Vec<(i32, i32)> = self.game.walls.iter().map(cell).collect();
+        walls.sort();
+        walls
+    }
+
+    /**
+     * Flat channel first grid of the board, see `observation_shape`.
+     */
+    fn observation(&self) -> Vec<f32> {
+        Observation::from_game(&self.game).data
+    }
+
+    fn observation_shape(&self) -> (usize, usize, usize) {
+        let [channels, rows, columns] = Observation::from_game(&self.game).shape();
+        (channels, rows, columns)
+    }
+}
+
+/**
+ * Gym style environment, `step` takes the index of an action in `actions`.
+ */
+#[pyclass(name = "Env")]
+pub struct PyEnv {
+    env: Env,
+}
+
+#[pymethods]
+impl PyEnv {
+    #[new]
+    #[pyo3(signature = (rows=12, columns=24, creepers=10, creeper_strategy="chase", max_steps=500, autopilot="greedy", connectivity="eight", wrap=false, maze="open", win_reward=None, loss_reward=None, step_reward=None, progress_reward=None, danger_reward=None))]
+    fn new(
+        rows: i32,
+        columns: i32,
+        creepers: i16,
+        creeper_strategy: &str,
+        max_steps: i16,
+        autopilot: &str,
+        connectivity: &str,
+        wrap: bool,
+        maze: &str,
+        win_reward: Option<f32>,
+        loss_reward: Option<f32>,
+        step_reward: Option<f32>,
+        progress_reward: Option<f32>,
+        danger_reward: Option<f32>,
+    ) -> PyResult<PyEnv> {
+        // Rewards that are not given keep their `RewardConfig` default.
+        let defaults = RewardConfig::default();
+        let config = EnvConfig {
+            game: game_config(
+                rows,
+                columns,
+                creepers,
+                creeper_strategy,
+                autopilot,
+                connectivity,
+                wrap,
+                maze,
+            )?,
+            rewards: RewardConfig {
+                win: win_reward.unwrap_or(defaults.win),
+                loss: loss_reward.unwrap_or(defaults.loss),
+                step: step_reward.unwrap_or(defaults.step),
+                progress: progress_reward.unwrap_or(defaults.progress),
+                danger: danger_reward.unwrap_or(defaults.danger),
+            },
+            max_steps,
+        };
+        Ok(PyEnv {
+            env: Env::new(config),
+        })
+    }
+
+    #[staticmethod]
+    fn actions() -> Vec<String> {
+        Action::all()
+            .iter()
+            .map(|action| match action {
+                Action::Stay => "stay".to_string(),
+                Action::Move(direction) => format!("{direction:?}").to_lowercase(),
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = (seed=0))]
+    fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.env.reset(seed).data
+    }
+
+    /**
+     * Returns the observation, the reward, whether the episode is over and a dict
+     * with the tick, the status and whether the episode ran out of steps.
+     */
+    fn step<'py>(
+        &mut self,
+        py: Python<'py>,
+        action: usize,
+    ) -> PyResult<(Vec<f32>, f32, bool, Bound<'py, PyDict>)> {
+        let action = Action::from_index(action)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown action {action}")))?;
+        let (observation, reward, done, info) = self.env.step(&action);
+        let dict = PyDict::new_bound(py);
+        dict.set_item("tick", info.tick)?;
+        dict.set_item("status", status_name(&info.status))?;
+        dict.set_item("truncated", info.truncated)?;
+        dict.set_item("closest_creeper", info.closest_creeper)?;
+        Ok((observation.data, reward, done, dict))
+    }
+
+    fn observation_shape(&self) -> (usize, usize, usize) {
+        let [channels, rows, columns] = self.env.observation().shape();
+        (channels, rows, columns)
+    }
+}
+
+#[pymodule]
+fn survival(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyGame>()?;
+    m.add_class::<PyEnv>()?;
+    Ok(())
+}
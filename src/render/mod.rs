@@ -0,0 +1,351 @@
+use crate::board::Board;
+use crate::model::{Direction, Game, GameState, Location};
+
+/**
+ * What is drawn on a cell, when several things share a cell only the one on top
+ * is kept.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sprite {
+    Lawn,
+    Wall,
+    Path,
+    Home,
+    Creeper,
+    Ferris,
+    // Ferris made it home.
+    FerrisHome,
+    // Ferris was caught by a creeper.
+    FerrisCaught,
+}
+
+impl Sprite {
+    /**
+     * Thumbnail the sprite is drawn with, the lawn, walls and the path do not have one.
+     */
+    pub fn image(&self) -> Option<&'static str> {
+        match self {
+            Sprite::Lawn | Sprite::Wall | Sprite::Path => None,
+            Sprite::Home => Some("thumbnail/home.png"),
+            Sprite::Creeper => Some("thumbnail/creeper2.png"),
+            Sprite::Ferris => Some("thumbnail/sadferris.png"),
+            Sprite::FerrisHome => Some("thumbnail/win.png"),
+            Sprite::FerrisCaught => Some("thumbnail/lost.png"),
+        }
+    }
+
+    /**
+     * Ferris and creepers move between ticks, renderers that animate draw them from
+     * `actors` instead of the frame.
+     */
+    pub fn is_actor(&self) -> bool {
+        matches!(
+            self,
+            Sprite::Creeper | Sprite::Ferris | Sprite::FerrisHome | Sprite::FerrisCaught
+        )
+    }
+}
+
+/**
+ * Something that moved on the last turn, from where it was on the previous state of
+ * the game to where it is now.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Actor {
+    pub sprite: Sprite,
+    pub from: Location,
+    pub to: Location,
+    // Where the actor was last heading to, it keeps facing that way while standing still.
+    pub facing: Direction,
+}
+
+impl Actor {
+    /**
+     * Position of the actor on screen in cells once `progress` (0 to 1) of the move is
+     * done, see `Board::screen_position`.
+     */
+    pub fn position(&self, board: &Board, progress: f64) -> (f64, f64) {
+        let progress = progress.clamp(0f64, 1f64);
+        let ((from_x, from_y), (to_x, to_y)) = (
+            board.screen_position(&self.from),
+            board.screen_position(&self.to),
+        );
+        // Moves through a wrapped edge jump to the other side instead of crossing the board.
+        let wrapped = (to_x - from_x).abs() > 1.5 || (to_y - from_y).abs() > 1.5;
+        let progress = if wrapped { 1f64 } else { progress };
+        let lerp = |from: f64, to: f64| from + (to - from) * progress;
+        (lerp(from_x, to_x), lerp(from_y, to_y))
+    }
+}
+
+/**
+ * Direction of a move, diagonal moves face left or right.
+ */
+fn heading(from: &Location, to: &Location) -> Option<Direction> {
+    if to.col() < from.col() {
+        Some(Direction::Left)
+    } else if to.col() > from.col() {
+        Some(Direction::Right)
+    } else if to.row() < from.row() {
+        Some(Direction::Up)
+    } else if to.row() > from.row() {
+        Some(Direction::Down)
+    } else {
+        None
+    }
+}
+
+/**
+ * Latest heading of an actor over the history of the game, `locate` finds the actor
+ * in a state. Actors that never moved face right.
+ */
+fn facing<F: Fn(&GameState) -> Option<&Location>>(moves: &[GameState], locate: F) -> Direction {
+    moves
+        .windows(2)
+        .rev()
+        .find_map(|pair| heading(locate(&pair[0])?, locate(&pair[1])?))
+        .unwrap_or(Direction::Right)
+}
+
+/**
+ * Ferris and every creeper with the move they made between the last two states of
+ * the game, Ferris comes last so it is drawn on top.
+ */
+pub fn actors(game: &Game) -> Vec<Actor> {
+    let state = match game.moves.last() {
+        Some(state) => state,
+        None => return vec![],
+    };
+    let previous = game
+        .moves
+        .len()
+        .checked_sub(2)
+        .map(|index| &game.moves[index])
+        .unwrap_or(state);
+    let mut actors: Vec<Actor> = state
+        .creepers
+        .iter()
+        .enumerate()
+        .map(|(index, creeper)| Actor {
+            sprite: Sprite::Creeper,
+            from: previous
+                .creepers
+                .get(index)
+                .map(|creeper| creeper.location.clone())
+                .unwrap_or_else(|| creeper.location.clone()),
+            to: creeper.location.clone(),
+            facing: facing(&game.moves, |state| {
+                state.creepers.get(index).map(|creeper| &creeper.location)
+            }),
+        })
+        .collect();
+    let ferris = &state.ferris.location;
+    let sprite = if *ferris == game.target {
+        Sprite::FerrisHome
+    } else if state
+        .creepers
+        .iter()
+        .any(|creeper| creeper.location == *ferris)
+    {
+        Sprite::FerrisCaught
+    } else {
+        Sprite::Ferris
+    };
+    actors.push(Actor {
+        sprite,
+        from: previous.ferris.location.clone(),
+        to: ferris.clone(),
+        facing: facing(&game.moves, |state| Some(&state.ferris.location)),
+    });
+    actors
+}
+
+/**
+ * Sprite of every cell of the board, built from the last state of the game in a
+ * single pass over creepers and Ferris's path instead of scanning them per cell.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub board: Board,
+    sprites: Vec<Sprite>,
+}
+
+impl Frame {
+    pub fn from_game(game: &Game) -> Frame {
+        let board = game.board();
+        let mut frame = Frame {
+            board,
+            sprites: vec![Sprite::Lawn; board.len()],
+        };
+        for wall in &game.walls {
+            frame.put(wall, Sprite::Wall);
+        }
+        let state = match game.moves.last() {
+            Some(state) => state,
+            None => {
+                frame.put(&game.target, Sprite::Home);
+                return frame;
+            }
+        };
+        // Later sprites are drawn on top of earlier ones.
+        for location in &state.ferris.path {
+            frame.put(location, Sprite::Path);
+        }
+        frame.put(&game.target, Sprite::Home);
+        for creeper in &state.creepers {
+            frame.put(&creeper.location, Sprite::Creeper);
+        }
+        let ferris = &state.ferris.location;
+        let ferris_sprite = if *ferris == game.target {
+            Sprite::FerrisHome
+        } else if frame.sprite(ferris) == Some(Sprite::Creeper) {
+            Sprite::FerrisCaught
+        } else {
+            Sprite::Ferris
+        };
+        frame.put(ferris, ferris_sprite);
+        frame
+    }
+
+    fn put(&mut self, location: &Location, sprite: Sprite) {
+        if let Some(index) = self.board.index(location) {
+            self.sprites[index] = sprite;
+        }
+    }
+
+    /**
+     * Sprite at `location`, None when it is off the board.
+     */
+    pub fn sprite(&self, location: &Location) -> Option<Sprite> {
+        self.board.index(location).map(|index| self.sprites[index])
+    }
+
+    /**
+     * Every cell that is not plain lawn, with its sprite.
+     */
+    pub fn cells(&self) -> impl Iterator<Item = (Location, Sprite)> + '_ {
+        self.sprites
+            .iter()
+            .enumerate()
+            .filter(|(_index, sprite)| **sprite != Sprite::Lawn)
+            .filter_map(|(index, sprite)| Some((self.board.location(index)?, *sprite)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::{Col, Row},
+        model::{Creeper, Direction, Ferris, Game, GameState, Location, Status},
+    };
+
+    use super::{actors, Frame, Sprite};
+
+    fn game(ferris: Location, creepers: Vec<Location>) -> Game {
+        Game {
+            moves: vec![GameState {
+                creepers: creepers
+                    .into_iter()
+                    .map(|location| Creeper { location })
+                    .collect(),
+                ferris: Ferris {
+                    location: ferris,
+                    path: vec![Location::at(Row(1), Col(1)), Location::at(Row(2), Col(2))],
+                },
+            }],
+            target: Location::at(Row(2), Col(2)),
+            status: Status::Playing,
+            ..Game::empty(3, 4)
+        }
+    }
+
+    #[test]
+    fn frame_layers_sprites() {
+        let frame = Frame::from_game(&game(
+            Location::at(Row(0), Col(0)),
+            vec![Location::at(Row(1), Col(1))],
+        ));
+        assert_eq!(
+            frame.sprite(&Location::at(Row(0), Col(0))),
+            Some(Sprite::Ferris)
+        );
+        // The creeper covers the path.
+        assert_eq!(
+            frame.sprite(&Location::at(Row(1), Col(1))),
+            Some(Sprite::Creeper)
+        );
+        assert_eq!(
+            frame.sprite(&Location::at(Row(2), Col(2))),
+            Some(Sprite::Home)
+        );
+        assert_eq!(
+            frame.sprite(&Location::at(Row(2), Col(3))),
+            Some(Sprite::Lawn)
+        );
+        assert_eq!(frame.sprite(&Location::at(Row(0), Col(4))), None);
+        let cells: Vec<(Location, Sprite)> = frame.cells().collect();
+        assert_eq!(
+            cells,
+            vec![
+                (Location::at(Row(0), Col(0)), Sprite::Ferris),
+                (Location::at(Row(1), Col(1)), Sprite::Creeper),
+                (Location::at(Row(2), Col(2)), Sprite::Home),
+            ]
+        );
+
+        let mut walled = game(Location::at(Row(0), Col(0)), vec![]);
+        walled.walls.insert(Location::at(Row(1), Col(3)));
+        let frame = Frame::from_game(&walled);
+        assert_eq!(
+            frame.sprite(&Location::at(Row(1), Col(3))),
+            Some(Sprite::Wall)
+        );
+    }
+
+    #[test]
+    fn frame_shows_how_the_game_ended() {
+        let frame = Frame::from_game(&game(Location::at(Row(2), Col(2)), vec![]));
+        assert_eq!(
+            frame.sprite(&Location::at(Row(2), Col(2))),
+            Some(Sprite::FerrisHome)
+        );
+        let frame = Frame::from_game(&game(
+            Location::at(Row(0), Col(3)),
+            vec![Location::at(Row(0), Col(3))],
+        ));
+        assert_eq!(
+            frame.sprite(&Location::at(Row(0), Col(3))),
+            Some(Sprite::FerrisCaught)
+        );
+    }
+
+    #[test]
+    fn actors_move_between_the_last_two_states() {
+        let mut game = game(
+            Location::at(Row(0), Col(0)),
+            vec![Location::at(Row(2), Col(3))],
+        );
+        let mut state = game.moves.last().unwrap().clone();
+        state.ferris.location = Location::at(Row(1), Col(1));
+        state.creepers[0].location = Location::at(Row(1), Col(3));
+        game.moves.push(state.clone());
+        // Standing still keeps the last heading.
+        game.moves.push(state);
+
+        let moved = actors(&game);
+        assert_eq!(moved.len(), 2);
+        assert_eq!(moved[0].sprite, Sprite::Creeper);
+        assert_eq!(moved[0].from, moved[0].to);
+        assert_eq!(moved[0].facing, Direction::Up);
+        assert_eq!(moved[1].sprite, Sprite::Ferris);
+        assert_eq!(moved[1].facing, Direction::Right);
+
+        game.moves.pop();
+        let moved = actors(&game);
+        assert_eq!(moved[1].from, Location::at(Row(0), Col(0)));
+        assert_eq!(moved[1].to, Location::at(Row(1), Col(1)));
+        let board = game.board();
+        assert_eq!(moved[1].position(&board, 0.5), (0.5, 0.5));
+        assert_eq!(moved[1].position(&board, 2.0), (1.0, 1.0));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::board::Board;
+use crate::model::GameConfig;
+use rand::{rngs::StdRng, SeedableRng};
+use std::str::FromStr;
+
+// Largest number of rows or columns a link can ask for.
+pub const MAX_BOARD_SIZE: i32 = 64;
+
+/**
+ * A game that can be shared as a link, the seed and the settings lay out the same
+ * starting board in every browser.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedGame {
+    pub seed: u64,
+    pub config: GameConfig,
+}
+
+impl SharedGame {
+    /**
+     * Query string of a link to the game, without the leading `?`.
+     */
+    pub fn to_query(&self) -> String {
+        let config = &self.config;
+        format!(
+            "seed={}&rows={}&columns={}&creepers={}&strategy={}&autopilot={}&connectivity={}&wrap={}&maze={}",
+            self.seed,
+            config.rows,
+            config.columns,
+            config.creepers,
+            config.creeper_strategy,
+            config.autopilot,
+            config.connectivity,
+            config.wrap,
+            config.maze
+        )
+    }
+
+    /**
+     * Game of a link made by `to_query`, a leading `?` or `#` is skipped. Settings
+     * that are missing or can not be read are taken from `fallback`, a board too small
+     * for its creepers gets fewer creepers.
+     */
+    pub fn from_query(query: &str, fallback: &SharedGame) -> SharedGame {
+        let mut shared = fallback.clone();
+        let query = query.trim_start_matches(['?', '#']);
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            let config = &mut shared.config;
+            match key {
+                "seed" => read(value, &mut shared.seed),
+                "rows" => read_size(value, &mut config.rows),
+                "columns" => read_size(value, &mut config.columns),
+                "creepers" => read(value, &mut config.creepers),
+                "strategy" => read(value, &mut config.creeper_strategy),
+                "autopilot" => read(value, &mut config.autopilot),
+                "connectivity" => read(value, &mut config.connectivity),
+                "wrap" => read(value, &mut config.wrap),
+                "maze" => read(value, &mut config.maze),
+                _ => {}
+            }
+        }
+        // Ferris and home need an open cell each. The seed lays out the same walls as it
+        // does when the game starts, they are the first thing `Game::new` draws.
+        let config = &mut shared.config;
+        let board = Board {
+            wrap: config.wrap,
+            ..Board::new(config.rows, config.columns, config.connectivity)
+        };
+        let walls = config
+            .maze
+            .walls(&board, &mut StdRng::seed_from_u64(shared.seed));
+        let room = board.len().saturating_sub(walls.len() + 2);
+        config.creepers = config.creepers.clamp(0, room.min(i16::MAX as usize) as i16);
+        shared
+    }
+}
+
+/**
+ * Replaces `setting` with `value` when it can be read.
+ */
+fn read<T: FromStr>(value: &str, setting: &mut T) {
+    if let Ok(value) = value.parse() {
+        *setting = value;
+    }
+}
+
+fn read_size(value: &str, size: &mut i32) {
+    match value.parse() {
+        Ok(value) if (2..=MAX_BOARD_SIZE).contains(&value) => *size = value,
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    use crate::{
+        autopilot::Autopilot,
+        hunt::CreeperStrategy,
+        maze::Maze,
+        model::{Connectivity, Game, GameConfig, Location},
+    };
+
+    use super::SharedGame;
+
+    fn fallback() -> SharedGame {
+        SharedGame {
+            seed: 1,
+            config: GameConfig {
+                creepers: 10,
+                rows: 12,
+                columns: 24,
+                creeper_strategy: CreeperStrategy::Chase,
+                autopilot: Autopilot::Greedy,
+                connectivity: Connectivity::Eight,
+                wrap: false,
+                maze: Maze::Open,
+            },
+        }
+    }
+
+    #[test]
+    fn links_reproduce_the_board() {
+        let shared = SharedGame {
+            seed: 987654321,
+            config: GameConfig {
+                creepers: 4,
+                rows: 9,
+                columns: 16,
+                creeper_strategy: CreeperStrategy::Pack,
+                connectivity: Connectivity::Hex,
+                wrap: true,
+                maze: Maze::Caves,
+                ..fallback().config
+            },
+        };
+        let link = format!("?{}", shared.to_query());
+        let opened = SharedGame::from_query(&link, &fallback());
+        assert_eq!(opened, shared);
+        let game = |shared: &SharedGame| {
+            Game::new(&shared.config, &mut StdRng::seed_from_u64(shared.seed))
+        };
+        assert_eq!(game(&opened), game(&shared));
+    }
+
+    #[test]
+    fn bad_settings_fall_back() {
+        let opened = SharedGame::from_query(
+            "#seed=12&rows=1000&columns=abc&strategy=sneaky&maze=prim&extra=1&wrap",
+            &fallback(),
+        );
+        assert_eq!(opened.seed, 12);
+        assert_eq!((opened.config.rows, opened.config.columns), (12, 24));
+        assert_eq!(opened.config.creeper_strategy, CreeperStrategy::Chase);
+        assert_eq!(opened.config.maze, Maze::Prim);
+        assert!(!opened.config.wrap);
+        assert_eq!(SharedGame::from_query("", &fallback()), fallback());
+
+        let crowded = SharedGame::from_query("rows=2&columns=2&creepers=9", &fallback());
+        assert_eq!(crowded.config.creepers, 2);
+        let negative = SharedGame::from_query("creepers=-3", &fallback());
+        assert_eq!(negative.config.creepers, 0);
+    }
+
+    #[test]
+    fn crowded_mazes_keep_spawns_apart() {
+        for seed in 0..10 {
+            let query = format!("seed={}&rows=2&columns=2&creepers=2&maze=caves", seed);
+            let opened = SharedGame::from_query(&query, &fallback());
+            let game = Game::new(&opened.config, &mut StdRng::seed_from_u64(opened.seed));
+            let open = 4 - game.walls.len();
+            assert_eq!(opened.config.creepers as usize, open - 2);
+            let state = game.moves.last().unwrap();
+            assert_eq!(state.creepers.len(), open - 2);
+            let mut cells: HashSet<&Location> = state
+                .creepers
+                .iter()
+                .map(|creeper| &creeper.location)
+                .collect();
+            assert!(cells.insert(&state.ferris.location));
+            assert!(cells.insert(&game.target));
+            assert!(cells.iter().all(|cell| !game.walls.contains(*cell)));
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::model::{Game, GameConfig, Status};
+use rand::{rngs::StdRng, SeedableRng};
+use std::ops::Range;
+
+/**
+ * Outcome of a single game played by the autopilot.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationResult {
+    pub seed: u64,
+    pub status: Status,
+    pub ticks: i16,
+}
+
+/**
+ * Outcome of a batch of games played with the same config.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchSummary {
+    pub games: u32,
+    pub won: u32,
+    pub lost: u32,
+    pub errors: u32,
+    // Games still going after the maximum number of ticks.
+    pub timeouts: u32,
+    pub won_ticks: u64,
+}
+
+impl BatchSummary {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0f64;
+        }
+        self.won as f64 / self.games as f64
+    }
+
+    pub fn average_ticks_to_win(&self) -> f64 {
+        if self.won == 0 {
+            return 0f64;
+        }
+        self.won_ticks as f64 / self.won as f64
+    }
+}
+
+/**
+ * Plays a whole game with `seed` without any keyboard input, Ferris follows the
+ * configured autopilot until the game ends or `max_ticks` went by.
+ */
+pub fn simulate(config: &GameConfig, seed: u64, max_ticks: i16) -> SimulationResult {
+    let mut randy = StdRng::seed_from_u64(seed);
+    let mut game = Game::new(config, &mut randy);
+    // Pressing an arrow key starts the game, the autopilot takes it from there.
+    if game.status == Status::Idle {
+        game.status = Status::Playing;
+    }
+    let mut ticks = 0;
+    while game.status == Status::Playing && ticks < max_ticks {
+        ticks += 1;
+        game = game.tick(ticks);
+    }
+    SimulationResult {
+        seed,
+        status: game.status,
+        ticks,
+    }
+}
+
+/**
+ * Plays one game per seed and adds up the results. Autopilots with a time budget search
+ * deeper on faster machines, set it to `u32::MAX` to compare them repeatably.
+ */
+pub fn run_batch(config: &GameConfig, seeds: Range<u64>, max_ticks: i16) -> BatchSummary {
+    let mut summary = BatchSummary::default();
+    for seed in seeds {
+        let result = simulate(config, seed, max_ticks);
+        summary.games += 1;
+        match result.status {
+            Status::Won => {
+                summary.won += 1;
+                summary.won_ticks += result.ticks as u64;
+            }
+            Status::Lost => summary.lost += 1,
+            Status::Error(_) => summary.errors += 1,
+            Status::Idle | Status::Playing | Status::Paused => summary.timeouts += 1,
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        autopilot::Autopilot,
+        hunt::CreeperStrategy,
+        lookahead::LookaheadConfig,
+        maze::Maze,
+        mcts::MctsConfig,
+        model::{Connectivity, GameConfig, Status},
+    };
+
+    use super::{run_batch, simulate};
+
+    fn config() -> GameConfig {
+        GameConfig {
+            creepers: 4,
+            rows: 12,
+            columns: 12,
+            creeper_strategy: CreeperStrategy::Chase,
+            autopilot: Autopilot::Greedy,
+            connectivity: Connectivity::Eight,
+            wrap: false,
+            maze: Maze::Open,
+        }
+    }
+
+    #[test]
+    fn simulate_is_deterministic() {
+        let first = simulate(&config(), 7, 200);
+        let second = simulate(&config(), 7, 200);
+        assert_eq!(first, second);
+        assert_ne!(first.status, Status::Playing);
+    }
+
+    #[test]
+    fn simulate_lookahead_is_deterministic() {
+        let config = GameConfig {
+            autopilot: Autopilot::Lookahead(LookaheadConfig {
+                depth: 3,
+                time_budget_ms: u32::MAX,
+            }),
+            ..config()
+        };
+        let first = simulate(&config, 7, 200);
+        let second = simulate(&config, 7, 200);
+        assert_eq!(first, second);
+        assert_ne!(first.status, Status::Playing);
+    }
+
+    #[test]
+    fn simulate_monte_carlo_is_deterministic() {
+        let config = GameConfig {
+            autopilot: Autopilot::MonteCarlo(MctsConfig {
+                iterations: 4,
+                time_budget_ms: u32::MAX,
+                ..MctsConfig::default()
+            }),
+            ..config()
+        };
+        let first = simulate(&config, 7, 60);
+        let second = simulate(&config, 7, 60);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn run_batch_counts_every_game() {
+        let summary = run_batch(&config(), 0..10, 200);
+        assert_eq!(summary.games, 10);
+        assert_eq!(
+            summary.won + summary.lost + summary.errors + summary.timeouts,
+            10
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::model::{Game, Status};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/**
+ * Results of a group of finished games.
+ */
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Totals {
+    pub played: u32,
+    pub won: u32,
+    // Fewest Ferris moves of a win.
+    pub fastest_win: Option<usize>,
+    // Most ticks played in a game, won or lost.
+    pub longest_survival: u32,
+    pub best_score: u32,
+}
+
+impl Totals {
+    /**
+     * Share of the games that were won, between 0 and 1.
+     */
+    pub fn win_rate(&self) -> f64 {
+        if self.played == 0 {
+            return 0f64;
+        }
+        self.won as f64 / self.played as f64
+    }
+
+    fn record(&mut self, game: &Game) {
+        self.played += 1;
+        if game.status == Status::Won {
+            self.won += 1;
+            let moves = game.ferris_moves();
+            self.fastest_win = Some(self.fastest_win.map_or(moves, |best| best.min(moves)));
+        }
+        self.longest_survival = self.longest_survival.max(game.stats.ticks);
+        self.best_score = self.best_score.max(game.score());
+    }
+}
+
+/**
+ * Stats of a player, over every game and for every seed played.
+ */
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Stats {
+    pub overall: Totals,
+    pub seeds: BTreeMap<u64, Totals>,
+    // Wins in a row up to the last game, and the longest run of wins.
+    pub streak: u32,
+    pub best_streak: u32,
+}
+
+impl Stats {
+    /**
+     * Stats saved with `to_json`, fails when the json is not stats.
+     */
+    pub fn from_json(json: &str) -> Result<Stats> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /**
+     * Adds a finished game, `seed` is None for boards that were not picked by a seed
+     * like campaign levels, they only count towards the overall stats.
+     */
+    pub fn record(&mut self, seed: Option<u64>, game: &Game) {
+        self.overall.record(game);
+        if let Some(seed) = seed {
+            self.seeds.entry(seed).or_default().record(game);
+        }
+        if game.status == Status::Won {
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+    }
+
+    /**
+     * Up to `count` seeds with the best scores, best first.
+     */
+    pub fn leaderboard(&self, count: usize) -> Vec<(u64, &Totals)> {
+        let mut seeds: Vec<(u64, &Totals)> = self
+            .seeds
+            .iter()
+            .map(|(seed, totals)| (*seed, totals))
+            .collect();
+        seeds.sort_by(|(seed_a, a), (seed_b, b)| {
+            b.best_score.cmp(&a.best_score).then(seed_a.cmp(seed_b))
+        });
+        seeds.truncate(count);
+        seeds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        level::Level,
+        model::{Direction, Game, Status},
+    };
+
+    use super::Stats;
+
+    fn game(won: bool) -> Game {
+        let mut game = Level {
+            map: vec!["F..H".to_string(), "....".to_string()],
+            ..Level::default()
+        }
+        .game(&Default::default())
+        .unwrap();
+        for _step in 0..3 {
+            game = game.move_ferris(Direction::Right);
+        }
+        if !won {
+            game.status = Status::Lost;
+        }
+        game
+    }
+
+    #[test]
+    fn stats_track_wins_and_streaks() {
+        let mut stats = Stats::default();
+        stats.record(Some(7), &game(true));
+        stats.record(Some(7), &game(true));
+        stats.record(Some(3), &game(false));
+        stats.record(None, &game(true));
+
+        assert_eq!(stats.overall.played, 4);
+        assert_eq!(stats.overall.won, 3);
+        assert_eq!(stats.overall.win_rate(), 0.75);
+        assert_eq!(stats.overall.fastest_win, Some(3));
+        assert_eq!((stats.streak, stats.best_streak), (1, 2));
+        assert_eq!(stats.seeds[&7].played, 2);
+        assert_eq!(stats.seeds[&3].won, 0);
+        assert_eq!(stats.seeds.len(), 2);
+
+        let leaderboard: Vec<u64> = stats.leaderboard(5).iter().map(|(seed, _)| *seed).collect();
+        assert_eq!(leaderboard, vec![7, 3]);
+        assert_eq!(stats.leaderboard(1).len(), 1);
+    }
+
+    #[test]
+    fn stats_move_between_browsers() {
+        let mut stats = Stats::default();
+        stats.record(Some(u64::MAX), &game(true));
+        assert_eq!(Stats::from_json(&stats.to_json()).unwrap(), stats);
+        assert!(Stats::from_json("[1, 2]").is_err());
+        assert_eq!(Stats::from_json("{}").unwrap(), Stats::default());
+    }
+}